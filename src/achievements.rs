@@ -0,0 +1,230 @@
+use crate::storage;
+use crate::wordle::Wordle;
+use serde::{Deserialize, Serialize};
+use serenity::model::id::UserId;
+use std::collections::HashMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const ACHIEVEMENTS_FILE: &str = "achievements.ron";
+/* Days in a row with a won game needed for the streak achievement. */
+pub const STREAK_DAYS: u32 = 10;
+
+/* Achievements players unlock by winning games in a special way. Each can be unlocked once. */
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Achievement {
+    /* Won a game with at most two guesses. */
+    SolvedInTwo,
+    /* Won games on STREAK_DAYS days in a row. */
+    Streak,
+    HardMode,
+    /* Won a game without guessing any word with the letter E. */
+    NeverUsedE,
+    /* Made the winning guess of a group. */
+    GroupMvp,
+}
+
+/* Everything about a won game achievements of a player depend on. */
+pub struct Win<'a> {
+    pub wordle: &'a Wordle,
+    pub players: usize,
+    /* Whether the player made the winning guess. */
+    pub winner: bool,
+    /* Days in a row, up to today, on which the player has won a game. */
+    pub streak: u32,
+}
+
+impl Achievement {
+    /* All achievements in the order they are listed in. */
+    pub const ALL: [Achievement; 5] = [
+        Achievement::SolvedInTwo,
+        Achievement::Streak,
+        Achievement::HardMode,
+        Achievement::NeverUsedE,
+        Achievement::GroupMvp,
+    ];
+
+    fn is_earned(self, win: &Win) -> bool {
+        match self {
+            Achievement::SolvedInTwo => win.wordle.guesses <= 2,
+            Achievement::Streak => win.streak >= STREAK_DAYS,
+            Achievement::HardMode => win.wordle.rules.hard_mode,
+            Achievement::NeverUsedE => win
+                .wordle
+                .fields
+                .values()
+                .all(|row| row.iter().all(|field| field.letter != 'E')),
+            Achievement::GroupMvp => win.players > 1 && win.winner,
+        }
+    }
+}
+
+/* An achievement unlocked by a player. */
+#[derive(Serialize, Deserialize)]
+pub struct Unlocked {
+    pub achievement: Achievement,
+    /* Id of the game in the history the achievement was unlocked in. */
+    pub game: u64,
+    /* Time of unlocking, in seconds since the Unix epoch. */
+    pub unlocked: u64,
+}
+
+/* Achievements of all players, saved in the storage directory after every unlock. */
+pub struct Achievements {
+    players: HashMap<u64, Vec<Unlocked>>,
+    storage_dir: String,
+}
+
+impl Achievements {
+    pub fn load(storage_dir: &str) -> Result<Achievements, String> {
+        Ok(Achievements {
+            players: storage::load(storage_dir, ACHIEVEMENTS_FILE)?,
+            storage_dir: storage_dir.to_string(),
+        })
+    }
+
+    /* Unlocks achievements a player earned with a won game and returns the ones that are new. */
+    pub fn unlock(&mut self, player: UserId, game: u64, win: &Win) -> Vec<Achievement> {
+        let unlocked = self.players.entry(player.0).or_default();
+        let new: Vec<Achievement> = Achievement::ALL
+            .into_iter()
+            .filter(|achievement| achievement.is_earned(win))
+            .filter(|achievement| !unlocked.iter().any(|u| u.achievement == *achievement))
+            .collect();
+        if new.is_empty() {
+            return new;
+        }
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs())
+            .unwrap_or(0);
+        unlocked.extend(new.iter().map(|&achievement| Unlocked {
+            achievement,
+            game,
+            unlocked: now,
+        }));
+        storage::save(&self.storage_dir, ACHIEVEMENTS_FILE, &self.players);
+        new
+    }
+
+    /* Returns the achievements a player has unlocked, in the order they are listed in. */
+    pub fn of(&self, player: UserId) -> Vec<&Unlocked> {
+        let unlocked = match self.players.get(&player.0) {
+            Some(unlocked) => unlocked,
+            None => return Vec::new(),
+        };
+        Achievement::ALL
+            .iter()
+            .filter_map(|achievement| unlocked.iter().find(|u| u.achievement == *achievement))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wordle::Rules;
+
+    /* Creates an empty storage directory of a test in the temporary directory. */
+    fn storage_dir(test: &str) -> String {
+        let dir = std::env::temp_dir().join(format!("wordle-{}-{}", test, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir.to_str().unwrap().to_string()
+    }
+
+    /* Plays the given guesses in a game with the word CRANE. */
+    fn play(guesses: &[&str], hard_mode: bool) -> Wordle {
+        let rules = Rules {
+            guesses: 6,
+            time_limit: 0,
+            hard_mode,
+            max_pause: 0,
+        };
+        let mut wordle = Wordle::new("CRANE".to_string(), rules);
+        for guess in guesses {
+            wordle.guesses += 1;
+            wordle.add_fields(guess.to_string());
+        }
+        wordle
+    }
+
+    fn earned(win: &Win) -> Vec<Achievement> {
+        Achievement::ALL
+            .into_iter()
+            .filter(|achievement| achievement.is_earned(win))
+            .collect()
+    }
+
+    #[test]
+    fn achievements_depend_on_the_win() {
+        let wordle = play(&["SOLID", "BUMPY", "CRANE"], false);
+        let mut win = Win {
+            wordle: &wordle,
+            players: 1,
+            winner: true,
+            streak: STREAK_DAYS - 1,
+        };
+        assert!(earned(&win).is_empty());
+        win.streak = STREAK_DAYS;
+        assert_eq!(earned(&win), vec![Achievement::Streak]);
+
+        let wordle = play(&["SOLID", "CRANE"], true);
+        let win = Win {
+            wordle: &wordle,
+            players: 2,
+            winner: true,
+            streak: 1,
+        };
+        assert_eq!(
+            earned(&win),
+            vec![
+                Achievement::SolvedInTwo,
+                Achievement::HardMode,
+                Achievement::GroupMvp
+            ]
+        );
+    }
+
+    #[test]
+    fn never_used_e_checks_every_guess() {
+        let wordle = play(&["SOLID", "BUMPY", "TRACK"], false);
+        let win = Win {
+            wordle: &wordle,
+            players: 2,
+            winner: false,
+            streak: 1,
+        };
+        assert_eq!(earned(&win), vec![Achievement::NeverUsedE]);
+    }
+
+    #[test]
+    fn achievements_are_unlocked_once() {
+        let dir = storage_dir("achievements");
+        let mut achievements = Achievements::load(&dir).unwrap();
+        let wordle = play(&["SOLID", "CRANE"], false);
+        let win = Win {
+            wordle: &wordle,
+            players: 1,
+            winner: true,
+            streak: 1,
+        };
+        let player = UserId(1);
+        assert_eq!(
+            achievements.unlock(player, 1, &win),
+            vec![Achievement::SolvedInTwo]
+        );
+        assert!(achievements.unlock(player, 2, &win).is_empty());
+        assert_eq!(
+            achievements.unlock(UserId(2), 3, &win),
+            vec![Achievement::SolvedInTwo]
+        );
+
+        let achievements = Achievements::load(&dir).unwrap();
+        let unlocked = achievements.of(player);
+        assert_eq!(unlocked.len(), 1);
+        assert_eq!(unlocked[0].achievement, Achievement::SolvedInTwo);
+        assert_eq!(unlocked[0].game, 1);
+        assert!(achievements.of(UserId(3)).is_empty());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
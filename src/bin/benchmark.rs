@@ -0,0 +1,298 @@
+use project_dc_bot::solver;
+use project_dc_bot::wordle::{Result, Rules, Wordle, GUESSES};
+use project_dc_bot::words::{Words, DEFAULT_WORDS_URL};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::env;
+use std::process::ExitCode;
+use std::time::Instant;
+
+const USAGE: &str = "Usage: benchmark [--mode normal|hard|both] [--guesses <number>] [--pack <name> | --lang <code>]
+                 [--words-url <url or path>] [--data-dir <path>] [--json]
+
+Plays a game with the solver used for `!hint` for every answer on the list
+and reports how many guesses it needed.";
+
+/* Settings of the benchmark given in the command line. */
+struct Options {
+    modes: Vec<bool>,
+    guesses: u32,
+    pack: Option<String>,
+    lang: Option<String>,
+    words_url: String,
+    data_dir: String,
+    json: bool,
+}
+
+impl Options {
+    /* Parses the command line, falling back to the same environment variables as the bot. */
+    fn parse() -> std::result::Result<Options, String> {
+        let mut options = Options {
+            modes: vec![false, true],
+            guesses: GUESSES,
+            pack: None,
+            lang: None,
+            words_url: env::var("WORDLE_WORDS_URL").unwrap_or_else(|_| DEFAULT_WORDS_URL.into()),
+            data_dir: env::var("WORDLE_DATA_DIR").unwrap_or_else(|_| String::from(".")),
+            json: false,
+        };
+        let mut args = env::args().skip(1);
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().ok_or(format!("{} needs a value", arg));
+            match arg.as_str() {
+                "--mode" => {
+                    options.modes = match value()?.as_str() {
+                        "normal" => vec![false],
+                        "hard" => vec![true],
+                        "both" => vec![false, true],
+                        _ => return Err(String::from("--mode is normal, hard or both")),
+                    }
+                }
+                "--guesses" => {
+                    options.guesses = value()?
+                        .parse()
+                        .ok()
+                        .filter(|&guesses| guesses > 0)
+                        .ok_or(String::from("--guesses needs a positive number"))?
+                }
+                "--pack" => options.pack = Some(value()?.to_lowercase()),
+                "--lang" => options.lang = Some(value()?.to_lowercase()).filter(|l| l != "en"),
+                "--words-url" => options.words_url = value()?,
+                "--data-dir" => options.data_dir = value()?,
+                "--json" => options.json = true,
+                _ => return Err(format!("Unknown option {}", arg)),
+            }
+        }
+        /* Word packs are made of English words. */
+        if options.pack.is_some() && options.lang.is_some() {
+            return Err(String::from("--pack can't be used with --lang"));
+        }
+        Ok(options)
+    }
+}
+
+/* Results of the solver in one mode. */
+#[derive(Serialize)]
+struct ModeReport {
+    mode: &'static str,
+    first_guess: String,
+    solved: usize,
+    /* Average number of guesses in solved games. */
+    average: f64,
+    /* Number of games solved with each number of guesses. */
+    distribution: BTreeMap<u32, usize>,
+    /* Answers the solver didn't guess in time. */
+    failures: Vec<String>,
+    seconds: f64,
+}
+
+#[derive(Serialize)]
+struct Report {
+    list: String,
+    answers: usize,
+    max_guesses: u32,
+    /* Answers that appear on the list more than once. */
+    duplicates: Vec<String>,
+    /* Answers that wouldn't be accepted as guesses, so players could never type them. */
+    not_guessable: Vec<String>,
+    modes: Vec<ModeReport>,
+}
+
+/* Describes the results shown in the fields of a game, e.g. "GRRYR|GGRRG". */
+fn results_key(wordle: &Wordle) -> String {
+    let mut rounds: Vec<&u32> = wordle.fields.keys().collect();
+    rounds.sort_unstable();
+    let rows: Vec<String> = rounds
+        .into_iter()
+        .map(|round| {
+            wordle.fields[round]
+                .iter()
+                .map(|field| match field.square {
+                    Result::Green => 'G',
+                    Result::Yellow => 'Y',
+                    Result::Red => 'R',
+                })
+                .collect()
+        })
+        .collect();
+    rows.join("|")
+}
+
+/* Plays a game with the given answer, making every guess the way `!hint` suggests it.
+ * Returns the number of guesses if the game is solved. The solver always picks the same guess
+ * for the same results, so picked guesses are kept in the cache and reused by later games. */
+fn play(
+    answer: &str,
+    answers: &[&str],
+    guesses: &[(&str, Vec<char>)],
+    rules: Rules,
+    cache: &mut HashMap<String, Vec<char>>,
+) -> Option<u32> {
+    let mut wordle = Wordle::new(answer.to_string(), rules);
+    while wordle.guesses < rules.guesses {
+        let key = results_key(&wordle);
+        let guess = match cache.get(&key) {
+            Some(guess) => guess.clone(),
+            None => {
+                let candidates = solver::candidates(answers, &wordle.fields);
+                /* In hard mode only guesses following the hints can be made. */
+                let allowed: Vec<Vec<char>> = guesses
+                    .iter()
+                    .filter(|(word, _)| !rules.hard_mode || wordle.follows_hints(word))
+                    .map(|(_, chars)| chars.clone())
+                    .collect();
+                let (guess, _) = solver::best_guess(&allowed, &candidates)?;
+                cache.insert(key, guess.clone());
+                guess
+            }
+        };
+        let guess: String = guess.into_iter().collect();
+        let solved = guess == wordle.word;
+        wordle.guesses += 1;
+        wordle.add_fields(guess);
+        if solved {
+            return Some(wordle.guesses);
+        }
+    }
+    None
+}
+
+fn run_mode(answers: &[&str], guesses: &[(&str, Vec<char>)], rules: Rules) -> ModeReport {
+    let started = Instant::now();
+    let mut cache = HashMap::new();
+    let mut distribution = BTreeMap::new();
+    let mut failures = Vec::new();
+    for &answer in answers {
+        match play(answer, answers, guesses, rules, &mut cache) {
+            Some(count) => *distribution.entry(count).or_insert(0) += 1,
+            None => failures.push(answer.to_string()),
+        }
+    }
+    let solved: usize = distribution.values().sum();
+    let total: u32 = distribution
+        .iter()
+        .map(|(&count, &games)| count * games as u32)
+        .sum();
+    ModeReport {
+        mode: if rules.hard_mode { "hard" } else { "normal" },
+        first_guess: cache
+            .get("")
+            .map(|g| g.iter().collect())
+            .unwrap_or_default(),
+        solved,
+        average: total as f64 / solved.max(1) as f64,
+        distribution,
+        failures,
+        seconds: started.elapsed().as_secs_f64(),
+    }
+}
+
+fn print_report(report: &Report) {
+    println!("{}: {} answers", report.list, report.answers);
+    if !report.duplicates.is_empty() {
+        println!("Duplicated answers: {}", report.duplicates.join(", "));
+    }
+    if !report.not_guessable.is_empty() {
+        println!(
+            "Answers not accepted as guesses: {}",
+            report.not_guessable.join(", ")
+        );
+    }
+    for mode in &report.modes {
+        println!();
+        println!(
+            "{} mode, first guess {}: solved {} of {} with {:.3} guesses on average in {:.1} s",
+            mode.mode, mode.first_guess, mode.solved, report.answers, mode.average, mode.seconds
+        );
+        let most = mode.distribution.values().copied().max().unwrap_or(1);
+        for count in 1..=report.max_guesses {
+            let games = mode.distribution.get(&count).copied().unwrap_or(0);
+            let bar = "#".repeat((games * 50 + most - 1) / most.max(1));
+            println!("{:>3}: {:>6} {}", count, games, bar);
+        }
+        if !mode.failures.is_empty() {
+            println!("Failures: {}", mode.failures.join(", "));
+        }
+    }
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let options = match Options::parse() {
+        Ok(options) => options,
+        Err(why) => {
+            eprintln!("{}\n\n{}", why, USAGE);
+            return ExitCode::FAILURE;
+        }
+    };
+    let words = Words::new(&options.words_url, &options.data_dir).await;
+    if words.is_fallback() {
+        eprintln!("Couldn't load the list of words from {}", options.words_url);
+        return ExitCode::FAILURE;
+    }
+    let (pack, lang) = (options.pack.as_deref(), options.lang.as_deref());
+    let list = match (pack, lang) {
+        (Some(name), _) if words.packs.contains_key(name) => format!("pack {}", name),
+        (_, Some(code)) if words.languages.contains_key(code) => format!("language {}", code),
+        (None, None) => String::from("main list"),
+        _ => {
+            eprintln!("There is no such word pack or language");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let answers = words.answers(pack, lang);
+    let guesses: Vec<(&str, Vec<char>)> = words
+        .guess_list(pack, lang)
+        .into_iter()
+        .map(|word| (word, word.chars().collect()))
+        .collect();
+    let mut sorted = answers.clone();
+    sorted.sort_unstable();
+    let mut duplicates: Vec<String> = sorted
+        .windows(2)
+        .filter(|pair| pair[0] == pair[1])
+        .map(|pair| pair[0].to_string())
+        .collect();
+    duplicates.dedup();
+    let not_guessable = answers
+        .iter()
+        .filter(|answer| !words.is_allowed_guess(answer, pack, lang))
+        .map(|answer| answer.to_string())
+        .collect();
+
+    let modes = options
+        .modes
+        .iter()
+        .map(|&hard_mode| {
+            let rules = Rules {
+                guesses: options.guesses,
+                time_limit: 0,
+                hard_mode,
+                max_pause: 0,
+            };
+            run_mode(&answers, &guesses, rules)
+        })
+        .collect();
+    let report = Report {
+        list,
+        answers: answers.len(),
+        max_guesses: options.guesses,
+        duplicates,
+        not_guessable,
+        modes,
+    };
+
+    if options.json {
+        match serde_json::to_string_pretty(&report) {
+            Ok(json) => println!("{}", json),
+            Err(why) => {
+                eprintln!("Couldn't write the report: {}", why);
+                return ExitCode::FAILURE;
+            }
+        }
+    } else {
+        print_report(&report);
+    }
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,305 @@
+use project_dc_bot::solver;
+use project_dc_bot::wordle::{
+    is_correct_word, normalize, Result, Rules, Wordle, DEFAULT_SIZE, GUESSES,
+};
+use project_dc_bot::words::{Words, DEFAULT_WORDS_URL};
+use std::env;
+use std::io::{self, BufRead, Write};
+use std::process::ExitCode;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const GREEN: &str = "\x1b[30;42m";
+const YELLOW: &str = "\x1b[30;43m";
+const GRAY: &str = "\x1b[97;100m";
+const RESET: &str = "\x1b[0m";
+const KEYBOARD: [&str; 3] = ["QWERTYUIOP", "ASDFGHJKL", "ZXCVBNM"];
+
+const USAGE: &str =
+    "Usage: terminal [--hard] [--daily] [--guesses <number>] [--pack <name> | --lang <code>]
+                [--words-url <url or path>] [--data-dir <path>]
+
+Type a word to guess it. Other commands:
+  !hint     suggest a guess
+  !giveup   show the word and end the game
+  !new      start a new game
+  !quit     leave";
+
+/* Settings of the terminal game given in the command line. */
+struct Options {
+    hard_mode: bool,
+    daily: bool,
+    guesses: u32,
+    pack: Option<String>,
+    lang: Option<String>,
+    words_url: String,
+    data_dir: String,
+}
+
+impl Options {
+    /* Parses the command line, falling back to the same environment variables as the bot. */
+    fn parse() -> std::result::Result<Options, String> {
+        let mut options = Options {
+            hard_mode: false,
+            daily: false,
+            guesses: GUESSES,
+            pack: None,
+            lang: None,
+            words_url: env::var("WORDLE_WORDS_URL").unwrap_or_else(|_| DEFAULT_WORDS_URL.into()),
+            data_dir: env::var("WORDLE_DATA_DIR").unwrap_or_else(|_| String::from(".")),
+        };
+        let mut args = env::args().skip(1);
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().ok_or(format!("{} needs a value", arg));
+            match arg.as_str() {
+                "--hard" => options.hard_mode = true,
+                "--daily" => options.daily = true,
+                "--guesses" => {
+                    options.guesses = value()?
+                        .parse()
+                        .ok()
+                        .filter(|&guesses| guesses > 0)
+                        .ok_or(String::from("--guesses needs a positive number"))?
+                }
+                "--pack" => options.pack = Some(value()?.to_lowercase()),
+                "--lang" => options.lang = Some(value()?.to_lowercase()).filter(|l| l != "en"),
+                "--words-url" => options.words_url = value()?,
+                "--data-dir" => options.data_dir = value()?,
+                _ => return Err(format!("Unknown option {}", arg)),
+            }
+        }
+        /* Word packs are made of English words. */
+        if options.pack.is_some() && options.lang.is_some() {
+            return Err(String::from("--pack can't be used with --lang"));
+        }
+        Ok(options)
+    }
+}
+
+/* Prints a letter on the background of its colour. */
+fn coloured(letter: char, square: Option<Result>) -> String {
+    let colour = match square {
+        Some(Result::Green) => GREEN,
+        Some(Result::Yellow) => YELLOW,
+        Some(Result::Red) => GRAY,
+        None => return format!(" {} ", letter),
+    };
+    format!("{} {} {}", colour, letter, RESET)
+}
+
+fn print_board(wordle: &Wordle) {
+    println!();
+    for round in 1..=wordle.rules.guesses {
+        match wordle.fields.get(&round) {
+            Some(row) => {
+                let row: String = row
+                    .iter()
+                    .map(|field| coloured(field.letter, Some(field.square)))
+                    .collect();
+                println!("  {}", row);
+            }
+            None => println!("  {}", " _ ".repeat(wordle.word.chars().count())),
+        }
+    }
+    println!();
+}
+
+/* Returns the best known result of a letter: green, then yellow, then red. */
+fn letter_result(wordle: &Wordle, letter: char) -> Option<Result> {
+    let squares: Vec<Result> = wordle
+        .fields
+        .values()
+        .flatten()
+        .filter(|field| field.letter == letter)
+        .map(|field| field.square)
+        .collect();
+    [Result::Green, Result::Yellow, Result::Red]
+        .into_iter()
+        .find(|square| squares.contains(square))
+}
+
+/* Prints the keyboard with letters coloured by what is known about them.
+ * Letters outside of the keyboard, e.g. with diacritics, are shown in an extra row once guessed. */
+fn print_keyboard(wordle: &Wordle) {
+    for (indent, row) in KEYBOARD.iter().enumerate() {
+        let keys: String = row
+            .chars()
+            .map(|letter| coloured(letter, letter_result(wordle, letter)))
+            .collect();
+        println!("{}{}", "  ".repeat(indent + 1), keys);
+    }
+    let mut extra: Vec<char> = wordle
+        .fields
+        .values()
+        .flatten()
+        .map(|field| field.letter)
+        .filter(|letter| !KEYBOARD.iter().any(|row| row.contains(*letter)))
+        .collect();
+    extra.sort_unstable();
+    extra.dedup();
+    if !extra.is_empty() {
+        let keys: String = extra
+            .into_iter()
+            .map(|letter| coloured(letter, letter_result(wordle, letter)))
+            .collect();
+        println!("  {}", keys);
+    }
+    println!();
+}
+
+fn new_game(words: &Words, options: &Options) -> Wordle {
+    let (pack, lang) = (options.pack.as_deref(), options.lang.as_deref());
+    let word = if options.daily {
+        let day = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs() / (24 * 60 * 60))
+            .unwrap_or(0);
+        words.daily_word(day, pack, lang).to_string()
+    } else {
+        match (pack, lang) {
+            (Some(name), _) => words.packs[name].generate_word(&[]).to_string(),
+            (_, Some(code)) => words.languages[code].generate_word(&[]).to_string(),
+            _ => words.generate_word(&[]).word.clone(),
+        }
+    };
+    let rules = Rules {
+        guesses: options.guesses,
+        time_limit: 0,
+        hard_mode: options.hard_mode,
+        max_pause: 0,
+    };
+    let mut wordle = Wordle::new(word, rules);
+    wordle.pack = options.pack.clone();
+    wordle.lang = options.lang.clone();
+    wordle
+}
+
+/* Suggests the guess that narrows down the possible answers the most, the same way `!hint` does. */
+fn hint(wordle: &Wordle, words: &Words) {
+    let (pack, lang) = (wordle.pack.as_deref(), wordle.lang.as_deref());
+    let candidates = solver::candidates(&words.answers(pack, lang), &wordle.fields);
+    let guesses: Vec<Vec<char>> = words
+        .guess_list(pack, lang)
+        .into_iter()
+        .filter(|guess| !wordle.rules.hard_mode || wordle.follows_hints(guess))
+        .map(|guess| guess.chars().collect())
+        .collect();
+    match solver::best_guess(&guesses, &candidates) {
+        Some((guess, _)) => println!(
+            "{} words can still be the answer. Try {}.",
+            candidates.len(),
+            guess.into_iter().collect::<String>()
+        ),
+        None => println!("No word on the list matches your guesses."),
+    }
+}
+
+/* Checks a guess like the bot does and adds it to the board. Returns a reason if it can't be made. */
+fn guess(wordle: &mut Wordle, words: &Words, input: &str) -> std::result::Result<(), String> {
+    let guess = normalize(input);
+    if !is_correct_word(&guess) {
+        return Err(format!("A guess must be a {}-letter word.", DEFAULT_SIZE));
+    }
+    if !words.is_allowed_guess(&guess, wordle.pack.as_deref(), wordle.lang.as_deref()) {
+        return Err(String::from("This word is not on the list."));
+    }
+    if wordle.rules.hard_mode && !wordle.follows_hints(&guess) {
+        return Err(String::from(
+            "In hard mode every guess must use all hints from previous guesses.",
+        ));
+    }
+    wordle.guesses += 1;
+    wordle.add_fields(guess);
+    Ok(())
+}
+
+fn prompt(wordle: &Wordle) {
+    print!("Guess {}/{}> ", wordle.guesses + 1, wordle.rules.guesses);
+    io::stdout().flush().ok();
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let options = match Options::parse() {
+        Ok(options) => options,
+        Err(why) => {
+            eprintln!("{}\n\n{}", why, USAGE);
+            return ExitCode::FAILURE;
+        }
+    };
+    let words = Words::new(&options.words_url, &options.data_dir).await;
+    if words.is_fallback() {
+        eprintln!("Couldn't load the list of words from {}", options.words_url);
+        return ExitCode::FAILURE;
+    }
+    if let Some(pack) = options
+        .pack
+        .as_deref()
+        .filter(|p| !words.packs.contains_key(*p))
+    {
+        eprintln!("There is no word pack {}: {:?}", pack, words.pack_names());
+        return ExitCode::FAILURE;
+    }
+    if let Some(lang) = options
+        .lang
+        .as_deref()
+        .filter(|l| !words.languages.contains_key(*l))
+    {
+        eprintln!(
+            "There is no language {}: {:?}",
+            lang,
+            words.language_codes()
+        );
+        return ExitCode::FAILURE;
+    }
+
+    println!("{}\n", USAGE);
+    let mut wordle = new_game(&words, &options);
+    let mut finished = false;
+    prompt(&wordle);
+    for line in io::stdin().lock().lines() {
+        let line = match line {
+            Ok(line) => line,
+            Err(_) => break,
+        };
+        match line.trim() {
+            "" => {}
+            "!quit" => break,
+            "!new" => {
+                wordle = new_game(&words, &options);
+                finished = false;
+                print_board(&wordle);
+            }
+            _ if finished => println!("The game is over. Type !new to play again or !quit."),
+            "!hint" => hint(&wordle, &words),
+            "!giveup" => {
+                println!("The word was {}.", wordle.word);
+                finished = true;
+            }
+            input => match guess(&mut wordle, &words, input) {
+                Err(why) => println!("{}", why),
+                Ok(()) => {
+                    print_board(&wordle);
+                    let solved = wordle.fields[&wordle.guesses]
+                        .iter()
+                        .all(|field| field.square == Result::Green);
+                    if solved || wordle.guesses == wordle.rules.guesses {
+                        if !solved {
+                            println!("The word was {}.", wordle.word);
+                        }
+                        println!("{}\n", wordle.share_grid(solved));
+                        finished = true;
+                    } else {
+                        print_keyboard(&wordle);
+                    }
+                }
+            },
+        }
+        if finished {
+            print!("> ");
+            io::stdout().flush().ok();
+        } else {
+            prompt(&wordle);
+        }
+    }
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,45 @@
+use serenity::model::id::UserId;
+use std::time::{Duration, SystemTime};
+
+/* Time after which an unsolved challenge can be replaced by another player's challenge. */
+const OPEN_TIME: Duration = Duration::from_secs(24 * 60 * 60);
+
+/* Struct representing a word set by one player for others to guess in a given channel. */
+#[derive(Clone)]
+pub struct Challenge {
+    pub word: String,
+    pub setter: UserId,
+    /* People that have started a game with the challenge word. */
+    pub players: Vec<UserId>,
+    /* People that have guessed the challenge word. */
+    pub solvers: Vec<UserId>,
+    pub set_at: SystemTime,
+}
+
+impl Challenge {
+    pub fn new(word: String, setter: UserId) -> Challenge {
+        Challenge {
+            word,
+            setter,
+            players: Vec::new(),
+            solvers: Vec::new(),
+            set_at: SystemTime::now(),
+        }
+    }
+
+    /* A challenge is finished once somebody has solved it or it has been open for a day. */
+    pub fn is_finished(&self) -> bool {
+        !self.solvers.is_empty() || self.set_at.elapsed().unwrap_or_default() >= OPEN_TIME
+    }
+
+    /* Every player can try to guess a challenge word only once and the setter can't play at all. */
+    pub fn can_play(&self, player: UserId) -> bool {
+        player != self.setter && !self.players.contains(&player)
+    }
+
+    pub fn add_solver(&mut self, player: UserId) {
+        if !self.solvers.contains(&player) {
+            self.solvers.push(player);
+        }
+    }
+}
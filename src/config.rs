@@ -1,11 +1,145 @@
-
-use ron::{de};
+use crate::limits::RateLimits;
+use crate::wordle::{Rules, GUESSES};
+use crate::words::DEFAULT_WORDS_URL;
+use crate::{GAME_TIME, MAX_PAUSE};
+use ron::de;
 use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::net::SocketAddr;
+use std::str::FromStr;
+
+/* Used if no path is given in the command line or in the WORDLE_CONFIG environment variable. */
+const DEFAULT_CONFIG_PATH: &str = "config.ron";
+
+/* Format of logs written to the standard output. */
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub enum LogFormat {
+    Text,
+    Json,
+}
+
+impl FromStr for LogFormat {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<LogFormat, ()> {
+        match s.to_lowercase().as_str() {
+            "text" => Ok(LogFormat::Text),
+            "json" => Ok(LogFormat::Json),
+            _ => Err(()),
+        }
+    }
+}
+
+/* Errors that can happen while loading the configuration. */
+#[derive(Debug)]
+pub enum ConfigError {
+    Read(String, std::io::Error),
+    Parse(String, String),
+    MissingArgument(String),
+    InvalidValue(String, String),
+    MissingToken,
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Read(path, why) => write!(f, "cannot read {}: {}", path, why),
+            ConfigError::Parse(path, why) => write!(f, "cannot parse {}: {}", path, why),
+            ConfigError::MissingArgument(arg) => write!(f, "missing value of {}", arg),
+            ConfigError::InvalidValue(name, value) => {
+                write!(f, "invalid value of {}: '{}'", name, value)
+            }
+            ConfigError::MissingToken => write!(
+                f,
+                "no bot token, set it in the config file, in DISCORD_TOKEN or in a token file"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
 
+/* App's settings. Every setting except the token has a default value,
+ * so the config file only needs to contain the ones that are changed. */
 #[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Config {
     token: String,
+    /* File containing the token, e.g. a mounted secret. */
+    token_file: Option<String>,
     prefix: String,
+    guesses: u32,
+    /* Time to play a game or gather a group, in seconds. */
+    game_time: u64,
+    /* Total time a game can be paused for, in seconds. */
+    max_pause: u64,
+    words_url: String,
+    dictionary_url: String,
+    /* Directory containing the 'packs', 'languages' and 'locales' directories. */
+    data_dir: String,
+    /* Directory the bot saves its data in, e.g. settings of servers. */
+    storage_dir: String,
+    /* Filter of logs, e.g. "info" or "debug,serenity=warn". */
+    log_level: String,
+    log_format: LogFormat,
+    /* Address of the HTTP server for operators, e.g. "127.0.0.1:9100". It is not started if not set. */
+    http_addr: Option<SocketAddr>,
+    /* Token required by admin endpoints of the HTTP server, which are disabled if it's not set. */
+    admin_token: Option<String>,
+    rate_limits: RateLimits,
+}
+
+impl Default for Config {
+    fn default() -> Config {
+        Config {
+            token: String::new(),
+            token_file: None,
+            prefix: String::from("!"),
+            guesses: GUESSES,
+            game_time: GAME_TIME,
+            max_pause: MAX_PAUSE,
+            words_url: String::from(DEFAULT_WORDS_URL),
+            dictionary_url: String::from("https://api.dictionaryapi.dev/api/v2/entries/en/"),
+            data_dir: String::from("."),
+            storage_dir: String::from("storage"),
+            log_level: String::from("info"),
+            log_format: LogFormat::Text,
+            http_addr: None,
+            admin_token: None,
+            rate_limits: RateLimits::default(),
+        }
+    }
+}
+
+/* Returns the path given as `--config <path>` or `--config=<path>` in the command line,
+ * then the one in WORDLE_CONFIG. The flag tells whether the path was given explicitly. */
+fn config_path() -> Result<(String, bool), ConfigError> {
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "--config" {
+            return args
+                .next()
+                .map(|path| (path, true))
+                .ok_or(ConfigError::MissingArgument(arg));
+        }
+        if let Some(path) = arg.strip_prefix("--config=") {
+            return Ok((path.to_string(), true));
+        }
+    }
+    match std::env::var("WORDLE_CONFIG") {
+        Ok(path) => Ok((path, true)),
+        Err(_) => Ok((String::from(DEFAULT_CONFIG_PATH), false)),
+    }
+}
+
+/* Overwrites a setting with the value of an environment variable if it is set. */
+fn override_from_env<T: FromStr>(setting: &mut T, variable: &str) -> Result<(), ConfigError> {
+    if let Ok(value) = std::env::var(variable) {
+        *setting = value
+            .parse()
+            .map_err(|_| ConfigError::InvalidValue(variable.to_string(), value))?;
+    }
+    Ok(())
 }
 
 impl Config {
@@ -17,18 +151,161 @@ impl Config {
         self.prefix.as_str()
     }
 
-    /* Deserializes the configuration data from 'config.ron' and initializes app's settings. */
-    pub fn load() -> std::io::Result<Config> {
-        let input_path = format!("{}/config.ron", env!("CARGO_MANIFEST_DIR"));
-        let f = std::fs::File::open(&input_path).expect("Failed opening file");
-        let config: Config = match de::from_reader(&f) {
-            Ok(x) => x,
-            Err(e) => {
-                println!("Failed to load config: {}", e);
-                std::process::exit(1);
+    pub fn words_url(&self) -> &str {
+        self.words_url.as_str()
+    }
+
+    pub fn dictionary_url(&self) -> &str {
+        self.dictionary_url.as_str()
+    }
+
+    pub fn data_dir(&self) -> &str {
+        self.data_dir.as_str()
+    }
+
+    pub fn storage_dir(&self) -> &str {
+        self.storage_dir.as_str()
+    }
+
+    pub fn log_level(&self) -> &str {
+        self.log_level.as_str()
+    }
+
+    pub fn log_format(&self) -> LogFormat {
+        self.log_format
+    }
+
+    pub fn http_addr(&self) -> Option<SocketAddr> {
+        self.http_addr
+    }
+
+    pub fn admin_token(&self) -> Option<&str> {
+        self.admin_token.as_deref()
+    }
+
+    pub fn rate_limits(&self) -> RateLimits {
+        self.rate_limits
+    }
+
+    /* Rules of games that have not been changed for a server. */
+    pub fn rules(&self) -> Rules {
+        Rules {
+            guesses: self.guesses,
+            time_limit: self.game_time,
+            hard_mode: false,
+            max_pause: self.max_pause,
+        }
+    }
+
+    /* Deserializes the configuration data from the config file and initializes app's settings.
+     * If the default config file doesn't exist, the settings are only taken from the environment.
+     * Environment variables take precedence over the file, and DISCORD_TOKEN over the token file. */
+    pub fn load() -> Result<Config, ConfigError> {
+        let (path, explicit) = config_path()?;
+        let mut config: Config = match std::fs::File::open(&path) {
+            Ok(f) => de::from_reader(f).map_err(|why| ConfigError::Parse(path, why.to_string()))?,
+            Err(why) if explicit || why.kind() != std::io::ErrorKind::NotFound => {
+                return Err(ConfigError::Read(path, why))
             }
+            Err(_) => Config::default(),
         };
 
+        if let Ok(file) = std::env::var("DISCORD_TOKEN_FILE") {
+            config.token_file = Some(file);
+        }
+        override_from_env(&mut config.prefix, "WORDLE_PREFIX")?;
+        override_from_env(&mut config.guesses, "WORDLE_GUESSES")?;
+        override_from_env(&mut config.game_time, "WORDLE_GAME_TIME")?;
+        override_from_env(&mut config.max_pause, "WORDLE_MAX_PAUSE")?;
+        override_from_env(&mut config.words_url, "WORDLE_WORDS_URL")?;
+        override_from_env(&mut config.dictionary_url, "WORDLE_DICTIONARY_URL")?;
+        override_from_env(&mut config.data_dir, "WORDLE_DATA_DIR")?;
+        override_from_env(&mut config.storage_dir, "WORDLE_STORAGE_DIR")?;
+        override_from_env(&mut config.log_level, "WORDLE_LOG_LEVEL")?;
+        override_from_env(&mut config.log_format, "WORDLE_LOG_FORMAT")?;
+        if let Ok(addr) = std::env::var("WORDLE_HTTP_ADDR") {
+            config.http_addr =
+                Some(addr.parse().map_err(|_| {
+                    ConfigError::InvalidValue(String::from("WORDLE_HTTP_ADDR"), addr)
+                })?);
+        }
+        if let Ok(token) = std::env::var("WORDLE_ADMIN_TOKEN") {
+            config.admin_token = Some(token);
+        }
+        if config.admin_token.as_deref() == Some("") {
+            config.admin_token = None;
+        }
+
+        if let Some(file) = &config.token_file {
+            config.token = std::fs::read_to_string(file)
+                .map_err(|why| ConfigError::Read(file.clone(), why))?
+                .trim()
+                .to_string();
+        }
+        override_from_env(&mut config.token, "DISCORD_TOKEN")?;
+
+        if config.token.is_empty() {
+            return Err(ConfigError::MissingToken);
+        }
+        if config.guesses == 0 {
+            return Err(ConfigError::InvalidValue(
+                String::from("guesses"),
+                config.guesses.to_string(),
+            ));
+        }
+        if config.game_time == 0 {
+            return Err(ConfigError::InvalidValue(
+                String::from("game_time"),
+                config.game_time.to_string(),
+            ));
+        }
+
         Ok(config)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_settings_keep_their_defaults() {
+        let config: Config = de::from_str("(prefix: \"?\", guesses: 8)").unwrap();
+        assert_eq!(config.prefix(), "?");
+        assert_eq!(config.rules().guesses, 8);
+        assert_eq!(config.rules().time_limit, GAME_TIME);
+        assert_eq!(config.words_url(), DEFAULT_WORDS_URL);
+        assert_eq!(config.storage_dir(), "storage");
+        assert_eq!(config.rate_limits().user.commands, 5);
+        assert!(config.http_addr().is_none());
+    }
+
+    /* Every test uses its own variables, as tests run in parallel and share the environment. */
+    #[test]
+    fn environment_overrides_settings() {
+        let mut guesses = GUESSES;
+        override_from_env(&mut guesses, "WORDLE_TEST_UNSET_GUESSES").unwrap();
+        assert_eq!(guesses, GUESSES);
+
+        std::env::set_var("WORDLE_TEST_GUESSES", "9");
+        override_from_env(&mut guesses, "WORDLE_TEST_GUESSES").unwrap();
+        assert_eq!(guesses, 9);
+
+        std::env::set_var("WORDLE_TEST_LOG_FORMAT", "JSON");
+        let mut format = LogFormat::Text;
+        override_from_env(&mut format, "WORDLE_TEST_LOG_FORMAT").unwrap();
+        assert!(matches!(format, LogFormat::Json));
+    }
+
+    #[test]
+    fn invalid_environment_values_are_rejected() {
+        std::env::set_var("WORDLE_TEST_GAME_TIME", "soon");
+        let mut game_time = GAME_TIME;
+        let result = override_from_env(&mut game_time, "WORDLE_TEST_GAME_TIME");
+        assert!(matches!(
+            result,
+            Err(ConfigError::InvalidValue(name, value)) if name == "WORDLE_TEST_GAME_TIME" && value == "soon"
+        ));
+        assert_eq!(game_time, GAME_TIME);
+    }
+}
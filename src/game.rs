@@ -0,0 +1,47 @@
+use crate::wordle::Wordle;
+use serenity::model::id::{ChannelId, GuildId, MessageId, UserId};
+use std::ops::{Deref, DerefMut};
+use tracing::Span;
+
+/* A game played on Discord: the game itself together with where and how the bot runs it.
+ * It derefs to its Wordle, so the rules, guesses and board are used the same way as in the engine. */
+pub struct Game {
+    pub wordle: Wordle,
+    /* Message showing the board, edited after every guess. */
+    pub board_message_id: Option<MessageId>,
+    /* Person who set the word if the game is played as a challenge. */
+    pub challenge_setter: Option<UserId>,
+    /* Server the game is played on, none in direct messages. */
+    pub guild_id: Option<GuildId>,
+    /* Channel the game's thread was created on, none if the game isn't played in a thread. */
+    pub thread_parent: Option<ChannelId>,
+    /* Everything logged about the game is recorded in its span. */
+    pub span: Span,
+}
+
+impl Game {
+    pub fn new(wordle: Wordle) -> Game {
+        Game {
+            wordle,
+            board_message_id: None,
+            challenge_setter: None,
+            guild_id: None,
+            thread_parent: None,
+            span: Span::none(),
+        }
+    }
+}
+
+impl Deref for Game {
+    type Target = Wordle;
+
+    fn deref(&self) -> &Wordle {
+        &self.wordle
+    }
+}
+
+impl DerefMut for Game {
+    fn deref_mut(&mut self) -> &mut Wordle {
+        &mut self.wordle
+    }
+}
@@ -0,0 +1,247 @@
+use crate::game::Game;
+use crate::storage;
+use crate::wordle::{Rules, Wordle};
+use serde::{Deserialize, Serialize};
+use serenity::model::id::{ChannelId, GuildId, UserId};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::info;
+
+const HISTORY_FILE: &str = "history.ron";
+const DAY: u64 = 24 * 60 * 60;
+/* Number of games listed by `!history`. */
+pub const RECENT_GAMES: usize = 10;
+
+/* Describes how a game has ended. */
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Won,
+    Lost,
+    GaveUp,
+    /* The game was ended by a moderator. */
+    Ended,
+    TimedOut,
+}
+
+/* Struct representing a finished game, kept after the instance of Wordle is removed. */
+#[derive(Serialize, Deserialize)]
+pub struct GameRecord {
+    pub id: u64,
+    pub guild: Option<u64>,
+    pub channel: u64,
+    pub players: Vec<u64>,
+    pub word: String,
+    pub guesses: Vec<String>,
+    pub max_guesses: u32,
+    pub hard_mode: bool,
+    pub hints: u32,
+    pub pack: Option<String>,
+    pub lang: Option<String>,
+    pub outcome: Outcome,
+    /* Times the game started and ended at, in seconds since the Unix epoch. */
+    pub started: u64,
+    pub finished: u64,
+}
+
+impl GameRecord {
+    /* Rebuilds the game from its guesses, so that its board can be displayed again. */
+    pub fn replay(&self) -> Wordle {
+        let rules = Rules {
+            guesses: self.max_guesses,
+            time_limit: 0,
+            hard_mode: self.hard_mode,
+            max_pause: 0,
+        };
+        let mut wordle = Wordle::new(self.word.clone(), rules);
+        for guess in &self.guesses {
+            wordle.guesses += 1;
+            wordle.add_fields(guess.clone());
+        }
+        wordle
+    }
+}
+
+fn unix_time(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0)
+}
+
+/* All finished games. Each game is appended to the file in the storage directory when it ends,
+ * so that saving it doesn't take longer as the history grows. */
+pub struct History {
+    games: Vec<GameRecord>,
+    next_id: u64,
+    storage_dir: String,
+}
+
+impl History {
+    pub fn load(storage_dir: &str) -> History {
+        let games: Vec<GameRecord> = storage::load_records(storage_dir, HISTORY_FILE);
+        History {
+            next_id: games.iter().map(|game| game.id).max().unwrap_or(0),
+            games,
+            storage_dir: storage_dir.to_string(),
+        }
+    }
+
+    /* Saves a finished game and returns its id. */
+    pub fn record(
+        &mut self,
+        wordle: &Game,
+        channel: ChannelId,
+        players: &[UserId],
+        outcome: Outcome,
+        started: SystemTime,
+    ) -> u64 {
+        self.next_id += 1;
+        let mut rounds: Vec<&u32> = wordle.fields.keys().collect();
+        rounds.sort_unstable();
+        let guesses = rounds
+            .into_iter()
+            .map(|round| wordle.fields[round].iter().map(|f| f.letter).collect())
+            .collect();
+
+        let record = GameRecord {
+            id: self.next_id,
+            guild: wordle.guild_id.map(|g| g.0),
+            channel: channel.0,
+            players: players.iter().map(|p| p.0).collect(),
+            word: wordle.word.clone(),
+            guesses,
+            max_guesses: wordle.rules.guesses,
+            hard_mode: wordle.rules.hard_mode,
+            hints: wordle.hints,
+            pack: wordle.pack.clone(),
+            lang: wordle.lang.clone(),
+            outcome,
+            started: unix_time(started),
+            finished: unix_time(SystemTime::now()),
+        };
+        storage::append_record(&self.storage_dir, HISTORY_FILE, &record);
+        self.games.push(record);
+        wordle.span.in_scope(|| {
+            info!(
+                id = self.next_id,
+                ?outcome,
+                guesses = wordle.guesses,
+                hints = wordle.hints,
+                "Game finished"
+            )
+        });
+        self.next_id
+    }
+
+    pub fn get(&self, id: u64) -> Option<&GameRecord> {
+        self.games.iter().find(|game| game.id == id)
+    }
+
+    /* Returns the number of days in a row on which the player has won a game on any server,
+     * up to today or, if they haven't won today yet, up to yesterday. Days are counted in UTC. */
+    pub fn streak(&self, player: UserId) -> u32 {
+        let mut days: Vec<u64> = self
+            .games
+            .iter()
+            .filter(|game| game.outcome == Outcome::Won && game.players.contains(&player.0))
+            .map(|game| game.finished / DAY)
+            .collect();
+        days.sort_unstable();
+        days.dedup();
+
+        let mut day = unix_time(SystemTime::now()) / DAY;
+        if days.binary_search(&day).is_err() {
+            day -= 1;
+        }
+        let mut streak = 0;
+        while days.binary_search(&day).is_ok() {
+            streak += 1;
+            day -= 1;
+        }
+        streak
+    }
+
+    /* Returns the latest games played on a server, of everyone or of the given player, newest first. */
+    pub fn recent(
+        &self,
+        guild: Option<GuildId>,
+        player: Option<UserId>,
+        count: usize,
+    ) -> Vec<&GameRecord> {
+        self.games
+            .iter()
+            .rev()
+            .filter(|game| game.guild == guild.map(|g| g.0))
+            .filter(|game| player.is_none_or(|p| game.players.contains(&p.0)))
+            .take(count)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PLAYER: UserId = UserId(1);
+
+    /* Makes a record of a game of the player which has ended the given number of days ago. */
+    fn game(days_ago: u64, outcome: Outcome) -> GameRecord {
+        let finished = unix_time(SystemTime::now()) - days_ago * DAY;
+        GameRecord {
+            id: days_ago,
+            guild: Some(1),
+            channel: 1,
+            players: vec![PLAYER.0],
+            word: "CRANE".to_string(),
+            guesses: vec!["CRANE".to_string()],
+            max_guesses: 6,
+            hard_mode: false,
+            hints: 0,
+            pack: None,
+            lang: None,
+            outcome,
+            started: finished,
+            finished,
+        }
+    }
+
+    fn history(games: Vec<GameRecord>) -> History {
+        History {
+            next_id: games.len() as u64,
+            games,
+            storage_dir: String::new(),
+        }
+    }
+
+    #[test]
+    fn streak_counts_days_in_a_row() {
+        let games = vec![
+            game(0, Outcome::Won),
+            game(0, Outcome::Won),
+            game(1, Outcome::Won),
+            game(2, Outcome::Won),
+            game(4, Outcome::Won),
+        ];
+        assert_eq!(history(games).streak(PLAYER), 3);
+    }
+
+    #[test]
+    fn streak_lasts_until_the_end_of_today() {
+        let games = vec![game(1, Outcome::Won), game(2, Outcome::Won)];
+        assert_eq!(history(games).streak(PLAYER), 2);
+        let games = vec![game(2, Outcome::Won), game(3, Outcome::Won)];
+        assert_eq!(history(games).streak(PLAYER), 0);
+    }
+
+    #[test]
+    fn streak_counts_only_won_games_of_the_player() {
+        let mut other = game(1, Outcome::Won);
+        other.players = vec![2];
+        let games = vec![
+            game(0, Outcome::Won),
+            game(1, Outcome::Lost),
+            game(1, Outcome::GaveUp),
+            other,
+        ];
+        assert_eq!(history(games).streak(PLAYER), 1);
+        assert_eq!(history(Vec::new()).streak(PLAYER), 0);
+    }
+}
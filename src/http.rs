@@ -0,0 +1,264 @@
+use crate::metrics::METRICS;
+use crate::words::Words;
+use crate::{end_game, ServerMap};
+use hyper::header::{AUTHORIZATION, CONTENT_TYPE};
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Method, Request, Response, Server, StatusCode};
+use serde::Serialize;
+use serenity::client::bridge::gateway::ShardManager;
+use serenity::gateway::ConnectionStage;
+use serenity::http::Http;
+use serenity::model::id::{ChannelId, UserId};
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use tracing::{error, info, warn};
+
+/* Everything the HTTP server needs to answer operators. */
+pub struct HttpState {
+    pub server: Arc<Mutex<ServerMap>>,
+    pub shard_manager: Arc<Mutex<ShardManager>>,
+    pub discord: Arc<Http>,
+    /* Token required by admin endpoints, which are disabled if there is none. */
+    pub admin_token: Option<String>,
+    pub words_url: String,
+    pub data_dir: String,
+}
+
+/* Serves endpoints for operators on the given address until the bot stops. */
+pub async fn serve(addr: SocketAddr, state: Arc<HttpState>) {
+    let make_service = make_service_fn(move |_| {
+        let state = state.clone();
+        async move {
+            Ok::<_, Infallible>(service_fn(move |request| {
+                let state = state.clone();
+                async move { Ok::<_, Infallible>(route(request, &state).await) }
+            }))
+        }
+    });
+
+    let http_server = match Server::try_bind(&addr) {
+        Ok(builder) => builder.serve(make_service),
+        Err(why) => {
+            error!(%addr, error = %why, "Couldn't start the HTTP server");
+            return;
+        }
+    };
+    info!(%addr, "Serving the HTTP endpoints");
+    if let Err(why) = http_server.await {
+        error!(error = %why, "HTTP server error");
+    }
+}
+
+async fn route(request: Request<Body>, state: &HttpState) -> Response<Body> {
+    match (request.method(), request.uri().path()) {
+        (&Method::GET, "/metrics") => {
+            METRICS.update(&*state.server.lock().await);
+            Response::builder()
+                .header(CONTENT_TYPE, prometheus::TEXT_FORMAT)
+                .body(Body::from(METRICS.encode()))
+                .expect("Failed to build a response")
+        }
+        (&Method::GET, "/healthz") => health(state).await,
+        (&Method::GET, "/games") => games(state).await,
+        (&Method::POST, "/games/end") => match authorize(&request, state) {
+            Err(code) => status(code),
+            Ok(()) => end_games(request.uri().query().unwrap_or_default(), state).await,
+        },
+        (&Method::POST, "/words/reload") => match authorize(&request, state) {
+            Err(code) => status(code),
+            Ok(()) => reload_words(state).await,
+        },
+        _ => status(StatusCode::NOT_FOUND),
+    }
+}
+
+fn status(code: StatusCode) -> Response<Body> {
+    Response::builder()
+        .status(code)
+        .body(Body::from(code.canonical_reason().unwrap_or_default()))
+        .expect("Failed to build a response")
+}
+
+fn json<T: Serialize>(code: StatusCode, value: &T) -> Response<Body> {
+    match serde_json::to_string(value) {
+        Ok(body) => Response::builder()
+            .status(code)
+            .header(CONTENT_TYPE, "application/json")
+            .body(Body::from(body))
+            .expect("Failed to build a response"),
+        Err(_) => status(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+/* Compares secrets in time that depends only on their length, so that a token can't be guessed
+ * a byte at a time from how quickly wrong ones are rejected. */
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+/* Lets through requests with the admin token, sent as `Authorization: Bearer <token>`. */
+fn authorize(request: &Request<Body>, state: &HttpState) -> Result<(), StatusCode> {
+    let token = match &state.admin_token {
+        Some(token) => token,
+        None => return Err(StatusCode::FORBIDDEN),
+    };
+    let given = request
+        .headers()
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    if given.is_some_and(|given| constant_time_eq(given.as_bytes(), token.as_bytes())) {
+        Ok(())
+    } else {
+        warn!("Unauthorized request to an admin endpoint");
+        Err(StatusCode::UNAUTHORIZED)
+    }
+}
+
+#[derive(Serialize)]
+struct Health {
+    gateway: bool,
+    words: bool,
+}
+
+/* The bot is healthy if every shard is connected to the gateway
+ * and the main list of words has been fetched. */
+async fn health(state: &HttpState) -> Response<Body> {
+    let gateway = {
+        let shard_manager = state.shard_manager.lock().await;
+        let runners = shard_manager.runners.lock().await;
+        !runners.is_empty()
+            && runners
+                .values()
+                .all(|runner| runner.stage == ConnectionStage::Connected)
+    };
+    let words = !state.server.lock().await.words.is_fallback();
+    let code = if gateway && words {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    json(code, &Health { gateway, words })
+}
+
+/* A live game as listed by `/games`. The word is left out, so that the list is spoiler-free,
+ * and so are the players, as the endpoint needs no token. */
+#[derive(Serialize)]
+struct GameInfo {
+    guild: u64,
+    channel: u64,
+    players: usize,
+    /* Whether a group is still gathering players. */
+    lobby: bool,
+    guesses: u32,
+    max_guesses: u32,
+    hard_mode: bool,
+    pack: Option<String>,
+    lang: Option<String>,
+    paused: bool,
+    seconds_left: u64,
+}
+
+/* Lists games played on servers. Games in direct messages are private, so they are left out.
+ * Only reads the games: ones that ran out of time are ended by the timers. */
+async fn games(state: &HttpState) -> Response<Body> {
+    let wordle_map = state.server.lock().await;
+    let group = wordle_map.max_people_playing > 1;
+    let lobby = group && wordle_map.joined_people.len() < wordle_map.max_people_playing;
+    let games: Vec<GameInfo> = wordle_map
+        .games
+        .iter()
+        .filter_map(|(&(channel, _), (wordle, time))| {
+            let guild = wordle.guild_id?;
+            Some(GameInfo {
+                guild: guild.0,
+                channel: channel.0,
+                players: if group {
+                    wordle_map.joined_people.len()
+                } else {
+                    1
+                },
+                lobby,
+                guesses: wordle.guesses,
+                max_guesses: wordle.rules.guesses,
+                hard_mode: wordle.rules.hard_mode,
+                pack: wordle.pack.clone(),
+                lang: wordle.lang.clone(),
+                paused: wordle.is_paused(),
+                seconds_left: wordle.time_left(*time),
+            })
+        })
+        .collect();
+    json(StatusCode::OK, &games)
+}
+
+#[derive(Serialize)]
+struct Ended {
+    ended: usize,
+}
+
+/* Ends games on the channel or of the player given in the query, e.g. `?channel=<id>`,
+ * or all games with `?all=true`. A query without any of them is rejected, so that games
+ * aren't ended by mistake. Games in threads count as played on the thread's channel.
+ * A game of a group can be ended by giving any of its players. */
+async fn end_games(query: &str, state: &HttpState) -> Response<Body> {
+    let mut channel = None;
+    let mut player = None;
+    let mut all = false;
+    for (name, value) in query.split('&').filter_map(|pair| pair.split_once('=')) {
+        match (name, value.parse::<u64>()) {
+            ("channel", Ok(id)) => channel = Some(ChannelId(id)),
+            ("player", Ok(id)) => player = Some(UserId(id)),
+            ("all", _) if value == "true" => all = true,
+            _ => return status(StatusCode::BAD_REQUEST),
+        }
+    }
+    if channel.is_none() && player.is_none() && !all {
+        return status(StatusCode::BAD_REQUEST);
+    }
+
+    let mut wordle_map = state.server.lock().await;
+    let in_group = |user: UserId| {
+        wordle_map.max_people_playing > 1 && wordle_map.joined_people.contains(&user)
+    };
+    let keys: Vec<(ChannelId, UserId)> = wordle_map
+        .games
+        .iter()
+        .filter(|((c, _), (w, _))| {
+            channel.is_none_or(|channel| *c == channel || w.thread_parent == Some(channel))
+        })
+        .filter(|((_, p), _)| player.is_none_or(|player| *p == player || in_group(player)))
+        .map(|(&key, _)| key)
+        .collect();
+    let ended = keys.len();
+    for key in keys {
+        end_game(&state.discord, &mut wordle_map, key).await;
+    }
+    info!(ended, "Games ended by an operator");
+    json(StatusCode::OK, &Ended { ended })
+}
+
+#[derive(Serialize)]
+struct Reloaded {
+    words: usize,
+    packs: usize,
+    languages: usize,
+}
+
+/* Fetches the main list of words again and reloads packs and languages from the data directory.
+ * The games being played keep their words. */
+async fn reload_words(state: &HttpState) -> Response<Body> {
+    let words = Words::new(&state.words_url, &state.data_dir).await;
+    if words.is_fallback() {
+        return status(StatusCode::BAD_GATEWAY);
+    }
+    let reloaded = Reloaded {
+        words: words.words.len(),
+        packs: words.packs.len(),
+        languages: words.languages.len(),
+    };
+    state.server.lock().await.words = words;
+    json(StatusCode::OK, &reloaded)
+}
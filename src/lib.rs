@@ -0,0 +1,4 @@
+/* Game engine shared by the Discord bot and the terminal version of the game. */
+pub mod solver;
+pub mod wordle;
+pub mod words;
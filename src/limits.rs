@@ -0,0 +1,249 @@
+use serde::{Deserialize, Serialize};
+use serenity::model::id::{ChannelId, UserId};
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::time::Instant;
+
+/* Number of commands that can be used in a period of time. Zero commands means no limit. */
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Limit {
+    pub commands: u32,
+    pub seconds: u64,
+}
+
+/* Limits of commands that start games or post boards. */
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RateLimits {
+    /* Limit of every user, in all channels together. */
+    pub user: Limit,
+    /* Limit of every channel, for all users together. */
+    pub channel: Limit,
+    /* Invalid guesses in a row a user gets replies to. Later ones are ignored until a valid guess. */
+    pub invalid_guesses: u32,
+}
+
+impl Default for RateLimits {
+    fn default() -> RateLimits {
+        RateLimits {
+            user: Limit {
+                commands: 5,
+                seconds: 10,
+            },
+            channel: Limit {
+                commands: 15,
+                seconds: 10,
+            },
+            invalid_guesses: 5,
+        }
+    }
+}
+
+/* Token bucket which is refilled to its capacity over the period of its limit. */
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+    /* Whether the user has been told to wait since the bucket last let a command through. */
+    warned: bool,
+}
+
+impl Bucket {
+    /* Returns the number of seconds until a command can be used, zero if it can be used now. */
+    fn wait(&mut self, limit: Limit) -> u64 {
+        let rate = limit.commands as f64 / limit.seconds.max(1) as f64;
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.updated).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * rate).min(limit.commands as f64);
+        self.updated = now;
+        if self.tokens >= 1.0 {
+            0
+        } else {
+            ((1.0 - self.tokens) / rate).ceil() as u64
+        }
+    }
+
+    /* Checks whether the bucket has refilled since it was last used, so that it's the same as a new one. */
+    fn is_full(&self, limit: Limit, now: Instant) -> bool {
+        let rate = limit.commands as f64 / limit.seconds.max(1) as f64;
+        let elapsed = now.duration_since(self.updated).as_secs_f64();
+        self.tokens + elapsed * rate >= limit.commands as f64
+    }
+}
+
+/* Returns the bucket of a user or a channel, full if it hasn't been used yet. */
+fn bucket<K: Eq + Hash>(buckets: &mut HashMap<K, Bucket>, key: K, limit: Limit) -> &mut Bucket {
+    buckets.entry(key).or_insert_with(|| Bucket {
+        tokens: limit.commands as f64,
+        updated: Instant::now(),
+        warned: false,
+    })
+}
+
+/* Result of checking a command against the limits. */
+pub enum Verdict {
+    Allowed,
+    /* The command has to wait the given number of seconds. The flag tells whether to tell the user,
+     * which happens only once, so that the cooldown messages can't be used for flooding as well. */
+    Cooldown(u64, bool),
+}
+
+/* Keeps track of commands used by every user and in every channel. */
+pub struct RateLimiter {
+    limits: RateLimits,
+    users: HashMap<UserId, Bucket>,
+    channels: HashMap<ChannelId, Bucket>,
+    invalid_guesses: HashMap<UserId, u32>,
+}
+
+impl RateLimiter {
+    pub fn new(limits: RateLimits) -> RateLimiter {
+        RateLimiter {
+            limits,
+            users: HashMap::new(),
+            channels: HashMap::new(),
+            invalid_guesses: HashMap::new(),
+        }
+    }
+
+    /* Checks whether a user can use a command in a channel and takes it from both limits if so. */
+    pub fn check(&mut self, user: UserId, channel: ChannelId) -> Verdict {
+        let (user_limit, channel_limit) = (self.limits.user, self.limits.channel);
+        let user_wait = if user_limit.commands > 0 {
+            bucket(&mut self.users, user, user_limit).wait(user_limit)
+        } else {
+            0
+        };
+        let channel_wait = if channel_limit.commands > 0 {
+            bucket(&mut self.channels, channel, channel_limit).wait(channel_limit)
+        } else {
+            0
+        };
+
+        if user_wait > 0 || channel_wait > 0 {
+            /* The user is only told once, whichever limit they hit. */
+            let user_bucket = bucket(&mut self.users, user, user_limit);
+            let first = !user_bucket.warned;
+            user_bucket.warned = true;
+            return Verdict::Cooldown(user_wait.max(channel_wait), first);
+        }
+        if let Some(b) = self.users.get_mut(&user) {
+            b.tokens -= 1.0;
+            b.warned = false;
+        }
+        if let Some(b) = self.channels.get_mut(&channel) {
+            b.tokens -= 1.0;
+        }
+        Verdict::Allowed
+    }
+
+    /* Counts an invalid guess of a user. Returns whether the user should still get a reply to it. */
+    pub fn invalid_guess(&mut self, user: UserId) -> bool {
+        let count = self.invalid_guesses.entry(user).or_insert(0);
+        *count += 1;
+        *count <= self.limits.invalid_guesses
+    }
+
+    pub fn valid_guess(&mut self, user: UserId) {
+        self.invalid_guesses.remove(&user);
+    }
+
+    /* Forgets users and channels whose buckets have refilled, so that the limiter doesn't grow
+     * with everyone who has ever used a command. A new bucket is created full when it's needed again. */
+    pub fn prune(&mut self) {
+        let now = Instant::now();
+        let (user_limit, channel_limit) = (self.limits.user, self.limits.channel);
+        self.users
+            .retain(|_, bucket| !bucket.is_full(user_limit, now));
+        self.channels
+            .retain(|_, bucket| !bucket.is_full(channel_limit, now));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn limiter(user: u32, channel: u32) -> RateLimiter {
+        RateLimiter::new(RateLimits {
+            user: Limit {
+                commands: user,
+                seconds: 60,
+            },
+            channel: Limit {
+                commands: channel,
+                seconds: 60,
+            },
+            invalid_guesses: 2,
+        })
+    }
+
+    fn allowed(verdict: Verdict) -> bool {
+        matches!(verdict, Verdict::Allowed)
+    }
+
+    #[test]
+    fn users_wait_once_their_limit_is_used() {
+        let mut limiter = limiter(2, 0);
+        let (user, channel) = (UserId(1), ChannelId(10));
+        assert!(allowed(limiter.check(user, channel)));
+        assert!(allowed(limiter.check(user, ChannelId(11))));
+        assert!(matches!(
+            limiter.check(user, channel),
+            Verdict::Cooldown(30, true)
+        ));
+        /* The user is only told to wait once. */
+        assert!(matches!(
+            limiter.check(user, channel),
+            Verdict::Cooldown(_, false)
+        ));
+        assert!(allowed(limiter.check(UserId(2), channel)));
+    }
+
+    #[test]
+    fn channels_are_limited_for_all_users() {
+        let mut limiter = limiter(0, 2);
+        let channel = ChannelId(10);
+        assert!(allowed(limiter.check(UserId(1), channel)));
+        assert!(allowed(limiter.check(UserId(2), channel)));
+        assert!(!allowed(limiter.check(UserId(3), channel)));
+        assert!(allowed(limiter.check(UserId(3), ChannelId(11))));
+    }
+
+    #[test]
+    fn zero_commands_means_no_limit() {
+        let mut limiter = limiter(0, 0);
+        for _ in 0..100 {
+            assert!(allowed(limiter.check(UserId(1), ChannelId(10))));
+        }
+        assert!(limiter.users.is_empty() && limiter.channels.is_empty());
+    }
+
+    #[test]
+    fn invalid_guesses_get_replies_until_the_limit() {
+        let mut limiter = limiter(0, 0);
+        let user = UserId(1);
+        assert!(limiter.invalid_guess(user));
+        assert!(limiter.invalid_guess(user));
+        assert!(!limiter.invalid_guess(user));
+        limiter.valid_guess(user);
+        assert!(limiter.invalid_guess(user));
+    }
+
+    #[test]
+    fn refilled_buckets_are_pruned() {
+        let mut limiter = limiter(2, 2);
+        limiter.check(UserId(1), ChannelId(10));
+        limiter.check(UserId(2), ChannelId(11));
+        limiter.prune();
+        assert_eq!((limiter.users.len(), limiter.channels.len()), (2, 2));
+
+        /* The buckets of the first user and channel were last used a minute ago. */
+        let minute_ago = Instant::now() - Duration::from_secs(60);
+        limiter.users.get_mut(&UserId(1)).unwrap().updated = minute_ago;
+        limiter.channels.get_mut(&ChannelId(10)).unwrap().updated = minute_ago;
+        limiter.prune();
+        assert!(limiter.users.contains_key(&UserId(2)) && limiter.users.len() == 1);
+        assert!(limiter.channels.contains_key(&ChannelId(11)) && limiter.channels.len() == 1);
+    }
+}
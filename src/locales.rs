@@ -0,0 +1,181 @@
+use crate::storage;
+use ron::de;
+use serenity::model::id::UserId;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use tracing::error;
+
+/* Code of the language every catalog falls back to. */
+pub const DEFAULT_LOCALE: &str = "en";
+/* The default catalog is built into the bot, so it is always complete. */
+const DEFAULT_CATALOG: &str = include_str!("../locales/en.ron");
+const USER_LOCALES_FILE: &str = "locales.ron";
+
+/* Struct representing all messages sent by bot in a single language, keyed by their names.
+ * Messages may contain placeholders in braces, e.g. "{players}".
+ * Commands are written with the "{prefix}" placeholder, e.g. "`{prefix}start`". */
+pub struct Catalog {
+    messages: HashMap<String, String>,
+}
+
+impl Catalog {
+    /* Returns a copy of the catalog with the prefix of commands filled in. */
+    fn with_prefix(&self, prefix: &str) -> Catalog {
+        let messages = self
+            .messages
+            .iter()
+            .map(|(key, message)| (key.clone(), message.replace("{prefix}", prefix)))
+            .collect();
+        Catalog { messages }
+    }
+
+    /* Returns the text of a message. If a message is missing, returns its name. */
+    pub fn get<'a>(&'a self, key: &'a str) -> &'a str {
+        self.messages.get(key).map(|m| m.as_str()).unwrap_or(key)
+    }
+
+    /* Returns the text of a message with placeholders replaced by given values. */
+    pub fn format(&self, key: &str, values: &[(&str, String)]) -> String {
+        let mut message = self.get(key).to_string();
+        for (name, value) in values {
+            message = message.replace(&format!("{{{}}}", name), value);
+        }
+        message
+    }
+}
+
+/* Contains catalogs of all available languages and languages chosen by users.
+ * Languages chosen for servers are kept in their settings. */
+pub struct Locales {
+    /* Catalogs as they are written, with the placeholder of the prefix. */
+    templates: HashMap<String, Catalog>,
+    /* Catalogs with the prefix from the config file, used unless a server has its own. */
+    catalogs: HashMap<String, Arc<Catalog>>,
+    prefix: String,
+    /* Catalogs with prefixes chosen for servers, keyed by the language and the prefix,
+     * filled in when they are first needed. */
+    prefixed: Mutex<HashMap<(String, String), Arc<Catalog>>>,
+    /* Languages chosen by users, saved in the storage directory after every change. */
+    user_locales: HashMap<u64, String>,
+    storage_dir: String,
+}
+
+/* Reads catalogs from the 'locales' directory, keyed by file names.
+ * Messages missing in a catalog are taken from the default one. */
+fn load_catalogs(data_dir: &str, default: &HashMap<String, String>) -> HashMap<String, Catalog> {
+    let mut catalogs = HashMap::new();
+    let dir = Path::new(data_dir).join("locales");
+    let entries = match std::fs::read_dir(&dir) {
+        Ok(entries) => entries,
+        Err(why) => {
+            error!(error = %why, "Error reading locales");
+            return catalogs;
+        }
+    };
+
+    for path in entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+    {
+        if path.extension().and_then(|ext| ext.to_str()) != Some("ron") {
+            continue;
+        }
+        let code = match path.file_stem().and_then(|stem| stem.to_str()) {
+            Some(code) => code.to_lowercase(),
+            None => continue,
+        };
+        if code == DEFAULT_LOCALE {
+            continue;
+        }
+        let messages: Result<HashMap<String, String>, String> = std::fs::File::open(&path)
+            .map_err(|why| why.to_string())
+            .and_then(|f| de::from_reader(f).map_err(|why| why.to_string()));
+        match messages {
+            Err(why) => error!(locale = %code, error = %why, "Error loading a catalog"),
+            Ok(mut messages) => {
+                for (key, message) in default {
+                    messages
+                        .entry(key.clone())
+                        .or_insert_with(|| message.clone());
+                }
+                catalogs.insert(code, Catalog { messages });
+            }
+        }
+    }
+    catalogs
+}
+
+impl Locales {
+    /* Loads catalogs and fills in the prefix of commands from the config file.
+     * Languages chosen by users are loaded from the storage directory. */
+    pub fn new(data_dir: &str, storage_dir: &str, prefix: &str) -> Result<Locales, String> {
+        let default: HashMap<String, String> =
+            de::from_str(DEFAULT_CATALOG).expect("Failed to parse the default catalog");
+        let mut templates = load_catalogs(data_dir, &default);
+        templates.insert(String::from(DEFAULT_LOCALE), Catalog { messages: default });
+        let catalogs = templates
+            .iter()
+            .map(|(code, template)| (code.clone(), Arc::new(template.with_prefix(prefix))))
+            .collect();
+
+        Ok(Locales {
+            templates,
+            catalogs,
+            prefix: prefix.to_string(),
+            prefixed: Mutex::new(HashMap::new()),
+            user_locales: storage::load(storage_dir, USER_LOCALES_FILE)?,
+            storage_dir: storage_dir.to_string(),
+        })
+    }
+
+    /* Chooses the language of messages sent to a user and saves languages of all users. */
+    pub fn set_user_locale(&mut self, user: UserId, code: String) {
+        self.user_locales.insert(user.0, code);
+        storage::save(&self.storage_dir, USER_LOCALES_FILE, &self.user_locales);
+    }
+
+    pub fn contains(&self, code: &str) -> bool {
+        self.catalogs.contains_key(code)
+    }
+
+    /* Returns codes of all available languages in alphabetical order. */
+    pub fn codes(&self) -> Vec<&str> {
+        let mut codes: Vec<&str> = self.catalogs.keys().map(|code| code.as_str()).collect();
+        codes.sort_unstable();
+        codes
+    }
+
+    /* Returns the catalog of the given language or the default one if it's not available,
+     * with commands written with the given prefix or, if there is none, the one from the config file. */
+    pub fn catalog(&self, code: &str, prefix: Option<&str>) -> Arc<Catalog> {
+        let code = if self.catalogs.contains_key(code) {
+            code
+        } else {
+            DEFAULT_LOCALE
+        };
+        match prefix.filter(|p| *p != self.prefix) {
+            Some(prefix) => self
+                .prefixed
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner())
+                .entry((code.to_string(), prefix.to_string()))
+                .or_insert_with(|| Arc::new(self.templates[code].with_prefix(prefix)))
+                .clone(),
+            None => self.catalogs[code].clone(),
+        }
+    }
+
+    /* Returns the catalog chosen by a user, otherwise the one chosen for the server. */
+    pub fn user_catalog(
+        &self,
+        user: UserId,
+        guild_locale: Option<&str>,
+        prefix: Option<&str>,
+    ) -> Arc<Catalog> {
+        match self.user_locales.get(&user.0) {
+            Some(code) => self.catalog(code, prefix),
+            None => self.catalog(guild_locale.unwrap_or(DEFAULT_LOCALE), prefix),
+        }
+    }
+}
@@ -0,0 +1,53 @@
+use crate::config::{Config, LogFormat};
+use crate::metrics::DISPATCHED;
+use serenity::async_trait;
+use serenity::framework::{Framework, StandardFramework};
+use serenity::model::channel::Message;
+use serenity::prelude::Context;
+use std::time::Instant;
+use tracing::{field, info_span, warn, Instrument};
+use tracing_subscriber::EnvFilter;
+
+/* Sets up logging to the standard output with the level and format from the config.
+ * RUST_LOG takes precedence over the level from the config. */
+pub fn init(config: &Config) {
+    let (filter, invalid) = match EnvFilter::try_from_default_env()
+        .or_else(|_| EnvFilter::try_new(config.log_level()))
+    {
+        Ok(filter) => (filter, false),
+        Err(_) => (EnvFilter::new("info"), true),
+    };
+    let builder = tracing_subscriber::fmt().with_env_filter(filter);
+    match config.log_format() {
+        LogFormat::Text => builder.init(),
+        LogFormat::Json => builder.json().init(),
+    }
+    if invalid {
+        warn!(
+            log_level = config.log_level(),
+            "Invalid log level, logging at the info level"
+        );
+    }
+}
+
+/* Runs every command inside a span describing where it was sent and by whom.
+ * The name of the command is recorded in the span once it's known.
+ * The time of dispatching is kept, so that the time of handling commands can be measured. */
+pub struct TracedFramework(pub StandardFramework);
+
+#[async_trait]
+impl Framework for TracedFramework {
+    async fn dispatch(&self, ctx: Context, msg: Message) {
+        let span = info_span!(
+            "command",
+            guild = ?msg.guild_id.map(|g| g.0),
+            channel = msg.channel_id.0,
+            user = msg.author.id.0,
+            name = field::Empty,
+        );
+        DISPATCHED
+            .scope(Instant::now(), self.0.dispatch(ctx, msg))
+            .instrument(span)
+            .await
+    }
+}
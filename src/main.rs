@@ -1,33 +1,72 @@
+mod achievements;
+mod challenge;
 mod config;
+mod game;
+mod history;
+mod http;
+mod limits;
+mod locales;
+mod logging;
 mod messages;
-mod wordle;
-mod words;
+mod metrics;
+mod options;
+mod ratings;
+mod scores;
+mod settings;
+mod storage;
 
+use crate::achievements::{Achievement, Achievements, Win};
+use crate::challenge::Challenge;
+use crate::game::Game;
+use crate::history::{History, Outcome, RECENT_GAMES};
+use crate::http::HttpState;
+use crate::limits::{RateLimiter, Verdict};
+use crate::locales::{Catalog, Locales, DEFAULT_LOCALE};
+use crate::logging::TracedFramework;
 use crate::messages::*;
-use crate::wordle::{DEFAULT_SIZE, GUESSES};
+use crate::metrics::METRICS;
+use crate::options::StartOptions;
+use crate::ratings::Ratings;
+use crate::scores::{Play, Scores, Season, LEADERBOARD_SIZE};
+use crate::settings::{Home, Settings};
+use crate::wordle::{is_correct_word, normalize, Rules};
 use crate::words::Words;
 use config::Config;
-use serenity::futures::TryFutureExt;
+use project_dc_bot::{solver, wordle, words};
 
 use serenity::{
     async_trait,
     client::ClientBuilder,
-    framework::standard::{macros::command, macros::group, Args, CommandResult, StandardFramework},
+    framework::standard::{
+        macros::{check, command, group, hook},
+        Args, CommandOptions, CommandResult, Reason, StandardFramework,
+    },
+    http::Http,
     model::id::*,
     model::prelude::*,
     prelude::*,
+    utils::{parse_channel, parse_username},
 };
 use std::collections::HashMap;
+use std::process::ExitCode;
 use std::sync::Arc;
-use std::time::SystemTime;
+use std::time::{Duration, SystemTime};
 use std::vec::Vec;
 use string_builder::Builder;
 use tokio::sync::{Mutex, MutexGuard};
+use tracing::{debug, error, info, info_span, instrument, warn, Instrument, Span};
 use wordle::Wordle;
 
-/* Every solo player has 5 minutes to complete game.
- * Group players have 5 minutes to join a game and another 5 minutes to play. */
+/* By default every solo player has 5 minutes to complete game.
+ * Group players have 5 minutes to join a game and another 5 minutes to play.
+ * The time can be changed in the config file. */
 pub const GAME_TIME: u64 = 5 * 60;
+/* Games can be paused for 10 minutes in total, unless it's changed in the config file. */
+pub const MAX_PAUSE: u64 = 10 * 60;
+/* Players are warned when a minute of their game is left. */
+const WARNING_TIME: u64 = 60;
+/* Seconds between checks of the games' clocks. */
+const TIMER_INTERVAL: u64 = 5;
 
 /* Structure to share data across server. */
 struct ServerKey;
@@ -37,24 +76,70 @@ impl TypeMapKey for ServerKey {
 }
 
 /* Contains information on all instances of Wordle that have been started,
- * max people playing, vector of people that joined group play and all available words to guess. */
+ * max people playing, vector of people that joined group play, all available words to guess
+ * challenges set by players, languages of bot's messages, settings of servers,
+ * finished games and settings loaded from the config. */
 struct ServerMap {
-    games: HashMap<(ChannelId, UserId), (Wordle, SystemTime)>,
+    games: HashMap<(ChannelId, UserId), (Game, SystemTime)>,
     /* Takes value: one if there is at least one solo play or
      * max number of players in a group if there is a group play. */
     max_people_playing: usize,
     joined_people: Vec<UserId>,
     words: Words,
+    /* Each channel can have only one challenge at a time. */
+    challenges: HashMap<ChannelId, Challenge>,
+    locales: Locales,
+    settings: Settings,
+    history: History,
+    scores: Scores,
+    achievements: Achievements,
+    ratings: Ratings,
+    limiter: RateLimiter,
+    /* Rules every new game is started with, unless they are changed for a server. */
+    rules: Rules,
+    dictionary_url: String,
+    prefix: String,
 }
 
 impl ServerMap {
-    pub async fn new() -> ServerMap {
-        ServerMap {
+    /* Returns an error if saved data can't be loaded without losing it. */
+    pub async fn new(config: &Config) -> Result<ServerMap, String> {
+        Ok(ServerMap {
             games: HashMap::new(),
             max_people_playing: 1,
             joined_people: Vec::new(),
-            words: Words::new().await,
-        }
+            words: Words::new(config.words_url(), config.data_dir()).await,
+            challenges: HashMap::new(),
+            locales: Locales::new(config.data_dir(), config.storage_dir(), config.prefix())?,
+            settings: Settings::load(config.storage_dir())?,
+            history: History::load(config.storage_dir()),
+            scores: Scores::load(config.storage_dir()),
+            achievements: Achievements::load(config.storage_dir())?,
+            ratings: Ratings::load(config.storage_dir())?,
+            limiter: RateLimiter::new(config.rate_limits()),
+            rules: config.rules(),
+            dictionary_url: config.dictionary_url().to_string(),
+            prefix: config.prefix().to_string(),
+        })
+    }
+
+    /* Returns the catalog chosen by a user, otherwise the one chosen for the server. */
+    fn user_catalog(&self, user: UserId, guild: Option<GuildId>) -> Arc<Catalog> {
+        let settings = self.settings.guild(guild);
+        self.locales
+            .user_catalog(user, settings.locale.as_deref(), settings.prefix.as_deref())
+    }
+
+    /* Returns the catalog chosen for the server, used for messages sent to everyone. */
+    fn guild_catalog(&self, guild: Option<GuildId>) -> Arc<Catalog> {
+        let settings = self.settings.guild(guild);
+        let locale = settings.locale.as_deref().unwrap_or(DEFAULT_LOCALE);
+        self.locales.catalog(locale, settings.prefix.as_deref())
+    }
+
+    /* Returns rules of new games started on the server. */
+    fn guild_rules(&self, guild: Option<GuildId>) -> Rules {
+        self.settings.guild(guild).rules(self.rules)
     }
 }
 
@@ -73,13 +158,84 @@ fn clean_joined_and_max_playing(wordle_map: &mut MutexGuard<'_, ServerMap>) {
     wordle_map.max_people_playing = 1;
 }
 
+/* Saves the result of a finished challenge game and returns the challenge with the catalog
+ * of the person who set it, so that they can be sent the report.
+ * The game counts as a head-to-head game between the player and the setter for their ratings,
+ * unless it was ended by a moderator. Does nothing if the game was not a challenge
+ * or the challenge has been replaced in the meantime. */
+fn settle_challenge(
+    wordle_map: &mut MutexGuard<'_, ServerMap>,
+    channel: ChannelId,
+    player: UserId,
+    setter: Option<UserId>,
+    guild: Option<GuildId>,
+    outcome: Outcome,
+) -> Option<(Challenge, Arc<Catalog>)> {
+    let setter = setter?;
+    let catalog = wordle_map.user_catalog(setter, None);
+    let map = &mut **wordle_map;
+    let challenge = map.challenges.get_mut(&channel)?;
+    if challenge.setter != setter {
+        return None;
+    }
+    let solved = outcome == Outcome::Won;
+    if solved {
+        challenge.add_solver(player);
+    }
+    if let Some(guild) = guild.filter(|_| outcome != Outcome::Ended) {
+        let (winner, loser) = if solved {
+            (player, setter)
+        } else {
+            (setter, player)
+        };
+        let change = map.ratings.record(guild, winner, loser);
+        info!(
+            winner = winner.0,
+            loser = loser.0,
+            change,
+            "Ratings updated"
+        );
+    }
+    Some((challenge.clone(), catalog))
+}
+
+/* Saves the result of a finished challenge game and reports it to the person who set the challenge. */
+async fn finish_challenge(
+    http: &Http,
+    wordle_map: &mut MutexGuard<'_, ServerMap>,
+    channel: ChannelId,
+    player: UserId,
+    setter: Option<UserId>,
+    guild: Option<GuildId>,
+    outcome: Outcome,
+) {
+    if let Some((challenge, catalog)) =
+        settle_challenge(wordle_map, channel, player, setter, guild, outcome)
+    {
+        send_challenge_report(http, &challenge, &catalog).await;
+    }
+}
+
 struct Handler;
 
 #[async_trait]
 impl EventHandler for Handler {
+    async fn ready(&self, _: Context, ready: Ready) {
+        info!(
+            user = %ready.user.name,
+            guilds = ready.guilds.len(),
+            "Connected to Discord"
+        );
+    }
+
     /* The bot's response to adding a reaction to a message.
      * It checks whether a user playing Wordle has reacted with a white flag emoji,
      * meaning they have given up on guessing. The bot ends their game. */
+    #[instrument(skip_all, fields(
+        guild = ?_add_reaction.guild_id.map(|g| g.0),
+        channel = _add_reaction.channel_id.0,
+        user = ?_add_reaction.user_id.map(|u| u.0),
+    ))]
     async fn reaction_add(&self, _ctx: Context, _add_reaction: Reaction) {
         let user = if let Some(u) = _add_reaction.user_id {
             u
@@ -95,6 +251,8 @@ impl EventHandler for Handler {
             .expect("Failed to retrieve wordle map!")
             .lock()
             .await;
+        let catalog = wordle_map.user_catalog(user, _add_reaction.guild_id);
+        let dictionary_url = wordle_map.dictionary_url.clone();
         /* Indicates whether the game is played by one person or a group.*/
         let single_player = wordle_map.max_people_playing == 1;
 
@@ -106,92 +264,748 @@ impl EventHandler for Handler {
         /* Finding a game the reaction was added to.
          * The reaction must be added to the latest wordle display of the game,
          * otherwise the bot will not respond. */
-        let mut coll = wordle_map
+        let key = wordle_map
             .games
-            .iter_mut()
-            .filter(|(_, (w, _))| w.last_message_id == Some(_add_reaction.message_id));
-        if let Some((&(_, player), (wordle, _))) = coll.next() {
+            .iter()
+            .find(|(_, (w, _))| w.board_message_id == Some(_add_reaction.message_id))
+            .map(|(&key, _)| key);
+        if let Some(key) = key {
+            let player = key.1;
             /* Somebody else reacted to a player's game. */
             if single_player && player != user {
                 return;
             }
-            send_wordle_solution(wordle, &_add_reaction.channel_id, players, &_ctx.http).await;
             /* Removing information about the instance. */
-            wordle_map
-                .games
-                .retain(|_, (w, _)| w.last_message_id != Some(_add_reaction.message_id));
+            let (wordle, started) = wordle_map.games.remove(&key).unwrap();
+            let wordle = &wordle;
+            let setter = wordle.challenge_setter;
+            wordle_map.history.record(
+                wordle,
+                _add_reaction.channel_id,
+                &players,
+                Outcome::GaveUp,
+                started,
+            );
+            send_wordle_solution(
+                wordle,
+                &_add_reaction.channel_id,
+                players.clone(),
+                &_ctx.http,
+                &catalog,
+                &dictionary_url,
+            )
+            .await;
+            send_share_grid(
+                &_ctx.http,
+                &_add_reaction.channel_id,
+                wordle_map.settings.results_channel(wordle.guild_id, player),
+                wordle,
+                false,
+                players,
+                &catalog,
+            )
+            .await;
+            spawn_wrap_up(
+                &_ctx.http,
+                &wordle_map.words,
+                &wordle_map.settings,
+                wordle,
+                _add_reaction.channel_id,
+                &catalog,
+            );
             clean_joined_and_max_playing(&mut wordle_map);
+            finish_challenge(
+                &_ctx.http,
+                &mut wordle_map,
+                wordle.thread_parent.unwrap_or(_add_reaction.channel_id),
+                player,
+                setter,
+                wordle.guild_id,
+                Outcome::GaveUp,
+            )
+            .await;
         }
     }
 }
 
 #[command]
 async fn help(ctx: &Context, msg: &Message) -> CommandResult {
-    send_embed_message(ctx, msg, HELP_MSG).await
+    let wordle_data = ctx.data.read().await;
+    let wordle_map = wordle_data
+        .get::<ServerKey>()
+        .expect("Failed to retrieve wordle map!")
+        .lock()
+        .await;
+    let catalog = wordle_map.user_catalog(msg.author.id, msg.guild_id);
+
+    send_embed_text(
+        ctx,
+        msg,
+        &catalog,
+        &help_message(
+            &catalog,
+            &wordle_map.words,
+            wordle_map.guild_rules(msg.guild_id),
+        ),
+    )
+    .await
 }
 
-/* Removes all games that took longer than 5 minutes to play/gather enough players. */
-fn check_ended_games(wordle_map: &mut MutexGuard<'_, ServerMap>) {
-    wordle_map
+/* The answer and the guesses of a finished game, with all words the answer and the guesses could be. */
+struct Analysis {
+    answer: Vec<char>,
+    rows: Vec<Vec<char>>,
+    answers: Vec<Vec<char>>,
+    guesses: Vec<Vec<char>>,
+}
+
+/* What is left to do after a game has finished: analysing its guesses if the analysis
+ * is turned on for the server and archiving its thread. */
+struct WrapUp {
+    http: Arc<Http>,
+    channel: ChannelId,
+    thread: Option<ChannelId>,
+    analysis: Option<Analysis>,
+    catalog: Arc<Catalog>,
+    span: Span,
+}
+
+/* Gathers everything needed to wrap up a finished game, so that it can be done without the games locked. */
+fn wrap_up(
+    http: &Arc<Http>,
+    words: &Words,
+    settings: &Settings,
+    wordle: &Game,
+    channel: ChannelId,
+    catalog: &Arc<Catalog>,
+) -> WrapUp {
+    let mut wrap_up = WrapUp {
+        http: http.clone(),
+        channel,
+        thread: wordle.thread_parent.map(|_| channel),
+        analysis: None,
+        catalog: catalog.clone(),
+        span: wordle.span.clone(),
+    };
+    if !settings.guild(wordle.guild_id).analysis || wordle.fields.is_empty() {
+        return wrap_up;
+    }
+    let to_chars = |words: Vec<&str>| -> Vec<Vec<char>> {
+        words.into_iter().map(|w| w.chars().collect()).collect()
+    };
+    let pack = wordle.pack.as_deref();
+    let lang = wordle.lang.as_deref();
+    let answers = to_chars(words.answers(pack, lang));
+    let guesses = to_chars(words.guess_list(pack, lang));
+    let answer: Vec<char> = wordle.word.chars().collect();
+    let mut rounds: Vec<&u32> = wordle.fields.keys().collect();
+    rounds.sort_unstable();
+    let rows: Vec<Vec<char>> = rounds
+        .into_iter()
+        .map(|round| wordle.fields[round].iter().map(|f| f.letter).collect())
+        .collect();
+    wrap_up.analysis = Some(Analysis {
+        answer,
+        rows,
+        answers,
+        guesses,
+    });
+    wrap_up
+}
+
+impl WrapUp {
+    /* Sends the analysis to the game's channel in the background, then archives the game's thread. */
+    fn spawn(self) {
+        let WrapUp {
+            http,
+            channel,
+            thread,
+            analysis,
+            catalog,
+            span,
+        } = self;
+        tokio::spawn(
+            async move {
+                if let Some(input) = analysis {
+                    let analysis = tokio::task::spawn_blocking(move || {
+                        solver::analyse(&input.answer, &input.rows, &input.answers, &input.guesses)
+                    })
+                    .await;
+                    match analysis {
+                        Ok(analysis) => send_analysis(&http, &channel, &analysis, &catalog).await,
+                        Err(why) => error!(error = %why, "Error analysing the game"),
+                    }
+                }
+                if let Some(thread) = thread {
+                    archive_thread(&http, thread).await;
+                }
+            }
+            .instrument(span),
+        );
+    }
+}
+
+/* Analyses guesses of a finished game in the background and sends the analysis to its channel
+ * if it's turned on for the server, then archives the game's thread. */
+fn spawn_wrap_up(
+    http: &Arc<Http>,
+    words: &Words,
+    settings: &Settings,
+    wordle: &Game,
+    channel: ChannelId,
+    catalog: &Arc<Catalog>,
+) {
+    wrap_up(http, words, settings, wordle, channel, catalog).spawn();
+}
+
+/* Gives points for a won game to all its players and returns how many each of them got.
+ * Points of games in direct messages count on the home server of the player if there is one. */
+fn award_points(
+    scores: &mut Scores,
+    words: &Words,
+    settings: &Settings,
+    wordle: &Game,
+    game: u64,
+    players: &[UserId],
+    started: SystemTime,
+) -> u32 {
+    let difficulty = words.difficulty(&wordle.word, wordle.pack.as_deref(), wordle.lang.as_deref());
+    let play = Play::new(wordle, wordle.time_left(started), difficulty, players.len());
+    let points = play.points();
+    for &player in players {
+        let guild = wordle
+            .guild_id
+            .or_else(|| settings.home(player).map(|home| GuildId(home.guild)));
+        scores.add(player, game, guild, points);
+    }
+    wordle
+        .span
+        .in_scope(|| info!(points, difficulty, "Points awarded"));
+    points
+}
+
+/* Unlocks achievements the players earned by winning a game and returns the new ones.
+ * The winner is the player who made the winning guess. */
+fn unlock_achievements(
+    achievements: &mut Achievements,
+    history: &History,
+    wordle: &Game,
+    game: u64,
+    players: &[UserId],
+    winner: UserId,
+) -> Vec<(UserId, Achievement)> {
+    let mut unlocked = Vec::new();
+    for &player in players {
+        let win = Win {
+            wordle,
+            players: players.len(),
+            winner: player == winner,
+            streak: history.streak(player),
+        };
+        for achievement in achievements.unlock(player, game, &win) {
+            wordle
+                .span
+                .in_scope(|| info!(player = player.0, ?achievement, "Achievement unlocked"));
+            unlocked.push((player, achievement));
+        }
+    }
+    unlocked
+}
+
+/* A game removed after running out of time, together with its players. */
+struct EndedGame {
+    channel: ChannelId,
+    player: UserId,
+    wordle: Game,
+    players: Vec<UserId>,
+    /* Whether the game was a group that didn't gather enough players. */
+    lobby: bool,
+}
+
+/* Removes all games that took longer than their time limit to play/gather enough players
+ * and returns them, so that their players can be told. Games in which somebody has guessed
+ * are kept in the history. The group is reset when its game is removed, so that a new one can start. */
+#[must_use]
+fn check_ended_games(wordle_map: &mut MutexGuard<'_, ServerMap>) -> Vec<EndedGame> {
+    let keys: Vec<(ChannelId, UserId)> = wordle_map
         .games
-        .retain(|_, time| time.1.elapsed().expect("Failed to get time!").as_secs() < GAME_TIME);
+        .iter()
+        .filter(|(_, (wordle, time))| wordle.time_left(*time) == 0)
+        .map(|(&key, _)| key)
+        .collect();
+    let group = wordle_map.max_people_playing > 1;
+    let lobby = group && wordle_map.joined_people.len() < wordle_map.max_people_playing;
+
+    let mut ended = Vec::new();
+    for (channel, player) in keys {
+        let (wordle, time) = wordle_map.games.remove(&(channel, player)).unwrap();
+        let players = get_players(wordle_map, player);
+        if wordle.guesses > 0 {
+            wordle_map
+                .history
+                .record(&wordle, channel, &players, Outcome::TimedOut, time);
+        }
+        ended.push(EndedGame {
+            channel,
+            player,
+            wordle,
+            players,
+            lobby,
+        });
+    }
+    if group && wordle_map.games.is_empty() {
+        clean_joined_and_max_playing(wordle_map);
+    }
+    ended
+}
+
+/* Finds games that are about to run out of time and returns the warnings for their players,
+ * once per game, so that they can be sent without the games locked.
+ * Groups still gathering players see the countdown in the replies to `!start` and `!join` instead. */
+fn time_warnings(
+    wordle_map: &mut MutexGuard<'_, ServerMap>,
+) -> Vec<(ChannelId, Vec<UserId>, String)> {
+    let gathering = wordle_map.max_people_playing > 1
+        && wordle_map.joined_people.len() < wordle_map.max_people_playing;
+    if gathering {
+        return Vec::new();
+    }
+    let mut due = Vec::new();
+    for (&(channel, player), (wordle, time)) in wordle_map.games.iter_mut() {
+        let left = wordle.time_left(*time);
+        if wordle.warned
+            || left == 0
+            || left > WARNING_TIME
+            || wordle.rules.time_limit <= WARNING_TIME
+        {
+            continue;
+        }
+        wordle.warned = true;
+        due.push((channel, player, wordle.guild_id, left));
+    }
+    due.into_iter()
+        .map(|(channel, player, guild, left)| {
+            let players = get_players(wordle_map, player);
+            let catalog = wordle_map.user_catalog(player, guild);
+            let text = catalog.format(
+                TIME_WARNING_MSG,
+                &[("deadline", deadline(left).to_string())],
+            );
+            (channel, players, text)
+        })
+        .collect()
+}
+
+/* A game that ran out of time, with everything needed to tell its players,
+ * gathered while the games are locked. */
+struct TimeOut {
+    game: EndedGame,
+    catalog: Arc<Catalog>,
+    dictionary_url: String,
+    wrap_up: WrapUp,
+    /* The challenge the game was played in and the catalog of its setter. */
+    report: Option<(Challenge, Arc<Catalog>)>,
+}
+
+/* Ends games that ran out of time and saves their results, without sending anything,
+ * so that their players can be told once the games are unlocked. */
+fn time_out_games(http: &Arc<Http>, wordle_map: &mut MutexGuard<'_, ServerMap>) -> Vec<TimeOut> {
+    check_ended_games(wordle_map)
+        .into_iter()
+        .map(|game| {
+            let wordle = &game.wordle;
+            let catalog = wordle_map.user_catalog(game.player, wordle.guild_id);
+            let wrap_up = wrap_up(
+                http,
+                &wordle_map.words,
+                &wordle_map.settings,
+                wordle,
+                game.channel,
+                &catalog,
+            );
+            let report = settle_challenge(
+                wordle_map,
+                wordle.thread_parent.unwrap_or(game.channel),
+                game.player,
+                wordle.challenge_setter,
+                wordle.guild_id,
+                Outcome::TimedOut,
+            );
+            TimeOut {
+                dictionary_url: wordle_map.dictionary_url.clone(),
+                catalog,
+                wrap_up,
+                report,
+                game,
+            }
+        })
+        .collect()
+}
+
+/* Tells the players of a game that ran out of time that it's over and shows them the word.
+ * A group that didn't gather enough players is only told that its lobby has closed. */
+async fn announce_time_out(http: &Http, time_out: TimeOut) {
+    let TimeOut {
+        game,
+        catalog,
+        dictionary_url,
+        wrap_up,
+        report,
+    } = time_out;
+    let EndedGame {
+        channel,
+        wordle,
+        players,
+        lobby,
+        ..
+    } = game;
+    wordle
+        .span
+        .in_scope(|| info!(lobby, "Game ran out of time"));
+    let text = if lobby { LOBBY_CLOSED_MSG } else { TIME_UP_MSG };
+    if let Err(why) = send_message(catalog.get(text), Some(players.clone()), http, &channel).await {
+        error!(error = %why, "Error sending the message");
+        METRICS.api_errors.inc();
+    }
+    if !lobby {
+        send_wordle_solution(&wordle, &channel, players, http, &catalog, &dictionary_url).await;
+    }
+    wrap_up.spawn();
+    if let Some((challenge, catalog)) = report {
+        send_challenge_report(http, &challenge, &catalog).await;
+    }
+}
+
+/* Ends games that ran out of time and tells their players in the background. Used by every command
+ * and request that looks at games, so that a game never ends unannounced between ticks of the timers. */
+fn end_timed_out_games(http: &Arc<Http>, wordle_map: &mut MutexGuard<'_, ServerMap>) {
+    let time_outs = time_out_games(http, wordle_map);
+    if time_outs.is_empty() {
+        return;
+    }
+    let http = http.clone();
+    tokio::spawn(async move {
+        for time_out in time_outs {
+            announce_time_out(&http, time_out).await;
+        }
+    });
+}
+
+/* Keeps the clocks of all games running: every few seconds warns players whose time
+ * is running out and ends games that ran out of it, instead of waiting for the next command.
+ * Rate limits of users and channels that haven't used commands lately are forgotten as well.
+ * The games are only locked to find them, the messages are sent afterwards,
+ * so that a slow request to Discord or the dictionary doesn't hold up commands. */
+async fn run_timers(http: Arc<Http>, server: Arc<Mutex<ServerMap>>) {
+    let mut interval = tokio::time::interval(Duration::from_secs(TIMER_INTERVAL));
+    loop {
+        interval.tick().await;
+        let (warnings, time_outs) = {
+            let mut wordle_map = server.lock().await;
+            wordle_map.limiter.prune();
+            (
+                time_warnings(&mut wordle_map),
+                time_out_games(&http, &mut wordle_map),
+            )
+        };
+        for (channel, players, text) in warnings {
+            if let Err(why) = send_message(&text, Some(players), &http, &channel).await {
+                error!(error = %why, "Error sending the message");
+                METRICS.api_errors.inc();
+            }
+        }
+        for time_out in time_outs {
+            announce_time_out(&http, time_out).await;
+        }
+    }
+}
+
+/* Creates the span of a new game. Games outlive the commands starting them, so it has no parent. */
+fn game_span(msg: &Message, wordle: &Wordle) -> Span {
+    info_span!(
+        parent: None,
+        "game",
+        guild = ?msg.guild_id.map(|g| g.0),
+        channel = msg.channel_id.0,
+        player = msg.author.id.0,
+        pack = ?wordle.pack,
+        lang = ?wordle.lang,
+        hard_mode = wordle.rules.hard_mode,
+    )
+}
+
+/* Creates a thread for a new game from the message starting it, so that boards of games
+ * don't interleave on busy channels. Returns the channel the game is played on
+ * and the channel the thread was created on. Where threads can't be created, e.g. in direct messages,
+ * games are played on the channel itself. Threads of games that run out of time
+ * are archived by Discord after an hour without messages. */
+async fn create_game_thread(
+    ctx: &Context,
+    msg: &Message,
+    catalog: &Catalog,
+) -> (ChannelId, Option<ChannelId>) {
+    if msg.guild_id.is_none() {
+        return (msg.channel_id, None);
+    }
+    let name = catalog.format(THREAD_NAME_MSG, &[("player", msg.author.name.clone())]);
+    match msg
+        .channel_id
+        .create_public_thread(&ctx.http, msg.id, |t| {
+            t.name(name).auto_archive_duration(60)
+        })
+        .await
+    {
+        Ok(thread) => (thread.id, Some(msg.channel_id)),
+        Err(why) => {
+            warn!(error = %why, "Couldn't create a thread for the game");
+            (msg.channel_id, None)
+        }
+    }
+}
+
+async fn archive_thread(http: &Http, thread: ChannelId) {
+    if let Err(why) = thread.edit_thread(http, |t| t.archived(true)).await {
+        warn!(%thread, error = %why, "Couldn't archive the thread of a game");
+        METRICS.api_errors.inc();
+    }
+}
+
+/* Tells which word packs can be chosen. */
+fn unknown_pack(catalog: &Catalog, words: &Words) -> String {
+    catalog.format(
+        UNKNOWN_PACK_MSG,
+        &[("packs", words.pack_names().join(", "))],
+    )
+}
+
+/* Tells which languages can be chosen. */
+fn unknown_language(catalog: &Catalog, words: &Words) -> String {
+    let mut languages = vec!["en"];
+    languages.extend(words.language_codes());
+    catalog.format(UNKNOWN_LANGUAGE_MSG, &[("languages", languages.join(", "))])
 }
 
-async fn add_new_wordle(msg: &Message, wordle_map: &mut Arc<Mutex<ServerMap>>) {
-    let wordle = Wordle::new(wordle_map.lock().await.words.generate_word().word.clone());
+/* Creates a game with a random word from the given word pack, list of words in the given language
+ * or from the main list of words. Returns the channel the game is played on, or the reply
+ * if the pack or the language is not loaded, e.g. because the words were reloaded without it. */
+async fn add_new_wordle(
+    ctx: &Context,
+    msg: &Message,
+    catalog: &Catalog,
+    wordle_map: &mut Arc<Mutex<ServerMap>>,
+    options: StartOptions,
+) -> Result<ChannelId, String> {
+    let mut wordle = {
+        let map = wordle_map.lock().await;
+        let banned = &map.settings.guild(msg.guild_id).banned_words;
+        let word = match (&options.pack, &options.lang) {
+            (Some(name), _) => match map.words.packs.get(name) {
+                Some(pack) => pack.generate_word(banned).to_string(),
+                None => return Err(unknown_pack(catalog, &map.words)),
+            },
+            (_, Some(code)) => match map.words.languages.get(code) {
+                Some(language) => language.generate_word(banned).to_string(),
+                None => return Err(unknown_language(catalog, &map.words)),
+            },
+            _ => map.words.generate_word(banned).word.clone(),
+        };
+        Game::new(Wordle::new(word, map.guild_rules(msg.guild_id)))
+    };
+    let (channel, thread_parent) = create_game_thread(ctx, msg, catalog).await;
+    wordle.guild_id = msg.guild_id;
+    wordle.thread_parent = thread_parent;
+    wordle.pack = options.pack;
+    wordle.lang = options.lang;
+    wordle.span = game_span(msg, &wordle);
+    wordle.span.in_scope(|| info!("Game started"));
     wordle_map
         .lock()
         .await
         .games
-        .insert((msg.channel_id, msg.author.id), (wordle, SystemTime::now()));
+        .insert((channel, msg.author.id), (wordle, SystemTime::now()));
+    Ok(channel)
+}
+
+/* Starts a solo game with the word of the challenge set on the channel. */
+async fn start_challenge(
+    ctx: &Context,
+    msg: &Message,
+    catalog: &Catalog,
+    wordle_map: &mut Arc<Mutex<ServerMap>>,
+) -> CommandResult {
+    let mut wordle_map = wordle_map.lock().await;
+    let rules = wordle_map.guild_rules(msg.guild_id);
+    let challenge = match wordle_map.challenges.get_mut(&msg.channel_id) {
+        Some(c) => c,
+        None => return send_embed_message(ctx, msg, catalog, NO_CHALLENGE_MSG).await,
+    };
+    if !challenge.can_play(msg.author.id) {
+        return send_embed_message(ctx, msg, catalog, CHALLENGE_PLAYED_MSG).await;
+    }
+    challenge.players.push(msg.author.id);
+    let (word, setter) = (challenge.word.clone(), challenge.setter);
+
+    let (channel, thread_parent) = create_game_thread(ctx, msg, catalog).await;
+    let mut wordle = Game::new(Wordle::new(word, rules));
+    wordle.challenge_setter = Some(setter);
+    wordle.guild_id = msg.guild_id;
+    wordle.thread_parent = thread_parent;
+    wordle.span = game_span(msg, &wordle);
+    wordle
+        .span
+        .in_scope(|| info!(setter = setter.0, "Challenge started"));
+    wordle_map
+        .games
+        .insert((channel, msg.author.id), (wordle, SystemTime::now()));
+    send_embed_to(ctx, channel, catalog, catalog.get(GAME_STARTED_MSG)).await
 }
 
 #[command]
-async fn start(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+async fn start(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
     /* Gets shared data across whole server. */
     let mut wordle_data = ctx.data.write().await;
     let wordle_map = wordle_data
         .get_mut::<ServerKey>()
         .expect("Failed to retrieve wordle map!");
+    let catalog = wordle_map
+        .lock()
+        .await
+        .user_catalog(msg.author.id, msg.guild_id);
 
-    check_ended_games(&mut wordle_map.lock().await);
+    end_timed_out_games(&ctx.http, &mut wordle_map.lock().await);
 
     /* No one can start a game if a group is playing/gathering players. */
     if wordle_map.lock().await.max_people_playing > 1 {
-        return send_embed_message(ctx, msg, GROUP_PLAYING_MSG).await;
+        return send_embed_message(ctx, msg, &catalog, GROUP_PLAYING_MSG).await;
     }
 
-    /* Starting game for solo player. */
-    if args.is_empty() {
-        add_new_wordle(msg, wordle_map).await;
-        return send_embed_message(ctx, msg, GAME_STARTED_MSG).await;
+    /* Every game gets its own thread, so a player can only play one game at a time on a server. */
+    let playing = wordle_map
+        .lock()
+        .await
+        .games
+        .iter()
+        .find(|((_, p), (w, _))| *p == msg.author.id && w.guild_id == msg.guild_id)
+        .map(|(&(channel, _), _)| channel);
+    if let Some(channel) = playing {
+        let text = catalog.format(ALREADY_PLAYING_MSG, &[("channel", channel.0.to_string())]);
+        return send_embed_text(ctx, msg, &catalog, &text).await;
+    }
+
+    let mut options = match StartOptions::parse(args) {
+        Ok(o) => o,
+        Err(why) => return send_embed_message(ctx, msg, &catalog, why).await,
+    };
+
+    /* Games started without a pack or a language use the ones chosen for the server. */
+    if options.pack.is_none() && options.lang.is_none() {
+        let map = wordle_map.lock().await;
+        let settings = map.settings.guild(msg.guild_id);
+        options.pack = settings.pack.clone();
+        options.lang = settings.lang.clone();
+    }
+
+    if options.challenge {
+        return start_challenge(ctx, msg, &catalog, wordle_map).await;
+    }
+
+    if let Some(pack) = &options.pack {
+        let map = wordle_map.lock().await;
+        if !map.words.packs.contains_key(pack) {
+            let text = unknown_pack(&catalog, &map.words);
+            return send_embed_text(ctx, msg, &catalog, &text).await;
+        }
+    }
+
+    if let Some(lang) = &options.lang {
+        let map = wordle_map.lock().await;
+        if !map.words.languages.contains_key(lang) {
+            let text = unknown_language(&catalog, &map.words);
+            return send_embed_text(ctx, msg, &catalog, &text).await;
+        }
     }
 
-    let number_of_players: usize = args.single_quoted::<String>()?.parse().unwrap();
-    if number_of_players <= 1 {
-        return send_embed_message(ctx, msg, WRONG_PLAYERS_NUMBER_MSG).await;
+    /* Starting game for solo player. */
+    let number_of_players = options.number_of_players;
+    if number_of_players > 1 && msg.guild_id.is_none() {
+        return send_embed_message(ctx, msg, &catalog, GROUP_IN_DM_MSG).await;
+    }
+    if number_of_players == 1 {
+        return match add_new_wordle(ctx, msg, &catalog, wordle_map, options).await {
+            Ok(channel) => {
+                send_embed_to(ctx, channel, &catalog, catalog.get(GAME_STARTED_MSG)).await
+            }
+            Err(text) => send_embed_text(ctx, msg, &catalog, &text).await,
+        };
     }
 
     /* Group can't start a game if there are solo games. */
     if !wordle_map.lock().await.games.is_empty() {
-        return send_embed_message(ctx, msg, SOLO_PLAYING_MSG).await;
+        return send_embed_message(ctx, msg, &catalog, SOLO_PLAYING_MSG).await;
     }
 
     /* If there is a start for a group play, games map will contain
      * UserId of a person who initiated a game. */
-    add_new_wordle(msg, wordle_map).await;
+    if let Err(text) = add_new_wordle(ctx, msg, &catalog, wordle_map, options).await {
+        return send_embed_text(ctx, msg, &catalog, &text).await;
+    }
     wordle_map.lock().await.max_people_playing = number_of_players;
     wordle_map.lock().await.joined_people.push(msg.author.id);
-    send_embed_message(ctx, msg, WAIT_FOR_PLAYERS_MSG).await
+    let time_limit = wordle_map.lock().await.guild_rules(msg.guild_id).time_limit;
+    let text = format!(
+        "{} {}",
+        catalog.get(WAIT_FOR_PLAYERS_MSG),
+        lobby_countdown(&catalog, time_limit)
+    );
+    send_embed_text(ctx, msg, &catalog, &text).await
+}
+
+/* Tells when a group that is still gathering players closes its lobby. */
+fn lobby_countdown(catalog: &Catalog, time_left: u64) -> String {
+    catalog.format(
+        LOBBY_TIME_LEFT_MSG,
+        &[("deadline", deadline(time_left).to_string())],
+    )
 }
 
+/* Checks whether a game is played on the message's channel or in a thread created on it. */
 fn check_channel(wordle_map: &MutexGuard<'_, ServerMap>, msg: &Message) -> bool {
-    for &key in wordle_map.games.keys() {
-        if key.0 == msg.channel_id {
-            return true;
+    wordle_map.games.iter().any(|(&(channel, _), (wordle, _))| {
+        channel == msg.channel_id || wordle.thread_parent == Some(msg.channel_id)
+    })
+}
+
+/* Returns the channel a game thread was created on, or the channel itself if it's not a game thread.
+ * Settings limiting channels apply to the parent, so games can be played in their threads. */
+fn parent_channel(wordle_map: &ServerMap, channel: ChannelId) -> ChannelId {
+    wordle_map
+        .games
+        .iter()
+        .find_map(|(&(c, _), (wordle, _))| wordle.thread_parent.filter(|_| c == channel))
+        .unwrap_or(channel)
+}
+
+/* Replies to a command about a game the player doesn't play on the message's channel.
+ * If the game is played in a thread created on the channel, the player is pointed to it. */
+async fn reply_no_game(
+    ctx: &Context,
+    msg: &Message,
+    wordle_map: &ServerMap,
+    catalog: &Catalog,
+    player: UserId,
+) -> CommandResult {
+    let thread = wordle_map
+        .games
+        .iter()
+        .find(|((_, p), (w, _))| *p == player && w.thread_parent == Some(msg.channel_id))
+        .map(|(&(channel, _), _)| channel);
+    match thread {
+        Some(thread) => {
+            let text = catalog.format(GAME_IN_THREAD_MSG, &[("thread", thread.0.to_string())]);
+            send_embed_text(ctx, msg, catalog, &text).await
         }
+        None => send_embed_message(ctx, msg, catalog, START_PLAYING_MSG).await,
     }
-    false
 }
 
 /* Changes time for now in a games map. */
@@ -209,42 +1023,70 @@ async fn join(ctx: &Context, msg: &Message) -> CommandResult {
         .expect("Failed to retrieve wordle map!")
         .lock()
         .await;
+    let catalog = wordle_map.user_catalog(msg.author.id, msg.guild_id);
 
-    check_ended_games(&mut wordle_map);
+    end_timed_out_games(&ctx.http, &mut wordle_map);
 
     /* No one can join if no one initiated a group game. */
     if wordle_map.max_people_playing == 1 {
-        return send_embed_message(ctx, msg, START_GROUP_MSG).await;
+        return send_embed_message(ctx, msg, &catalog, START_GROUP_MSG).await;
     }
 
     if wordle_map.joined_people.len() == wordle_map.max_people_playing {
-        return send_embed_message(ctx, msg, GROUP_PLAYING_MSG).await;
+        return send_embed_message(ctx, msg, &catalog, GROUP_PLAYING_MSG).await;
     }
 
     if !check_channel(&wordle_map, msg) {
-        return send_embed_message(ctx, msg, WRONG_CHANNEL_MSG).await;
+        return send_embed_message(ctx, msg, &catalog, WRONG_CHANNEL_MSG).await;
     }
 
     if wordle_map.joined_people.contains(&msg.author.id) {
-        return send_embed_message(ctx, msg, ALREADY_JOINED_MSG).await;
+        return send_embed_message(ctx, msg, &catalog, ALREADY_JOINED_MSG).await;
     }
 
     wordle_map.joined_people.push(msg.author.id);
     if wordle_map.joined_people.len() != wordle_map.max_people_playing {
-        return send_embed_message(
-            ctx,
-            msg,
-            &format!(
-                "You successfully joined the group! To start the game wait for {} other people",
-                wordle_map.max_people_playing - wordle_map.joined_people.len()
-            ),
-        )
-        .await;
+        let missing = wordle_map.max_people_playing - wordle_map.joined_people.len();
+        let time_left = wordle_map
+            .games
+            .values()
+            .map(|(wordle, time)| wordle.time_left(*time))
+            .max()
+            .unwrap_or_default();
+        let text = format!(
+            "{} {}",
+            catalog.format(JOINED_GROUP_MSG, &[("players", missing.to_string())]),
+            lobby_countdown(&catalog, time_left)
+        );
+        return send_embed_text(ctx, msg, &catalog, &text).await;
     }
 
     /* If there are enough people in a group, reset the timer and start the game. */
     change_time(&mut wordle_map);
-    send_embed_message(ctx, msg, GAME_STARTED_MSG).await
+    send_embed_message(ctx, msg, &catalog, GAME_STARTED_MSG).await
+}
+
+/* Returns the player whose game the author of a message can play: the author in solo games
+ * or the person who started the group. Otherwise returns the reply explaining why they can't play. */
+fn game_author(
+    wordle_map: &MutexGuard<'_, ServerMap>,
+    msg: &Message,
+    catalog: &Catalog,
+) -> Result<UserId, String> {
+    if wordle_map.max_people_playing == 1 {
+        return Ok(msg.author.id);
+    }
+    if wordle_map.joined_people.len() != wordle_map.max_people_playing {
+        let missing = wordle_map.max_people_playing - wordle_map.joined_people.len();
+        return Err(catalog.format(WAIT_FOR_GROUP_MSG, &[("players", missing.to_string())]));
+    }
+    if !check_channel(wordle_map, msg) {
+        return Err(catalog.get(GUESS_WRONG_CHANNEL_MSG).to_string());
+    }
+    if !wordle_map.joined_people.contains(&msg.author.id) {
+        return Err(catalog.get(NOT_IN_GROUP_MSG).to_string());
+    }
+    Ok(wordle_map.joined_people[0])
 }
 
 fn clean_game(wordle_map: &mut MutexGuard<'_, ServerMap>, msg: &Message, author: UserId) {
@@ -260,35 +1102,88 @@ async fn giveup(ctx: &Context, msg: &Message) -> CommandResult {
         .expect("Failed to retrieve wordle map!")
         .lock()
         .await;
+    let catalog = wordle_map.user_catalog(msg.author.id, msg.guild_id);
 
     let mut author = msg.author.id;
     if wordle_map.max_people_playing > 1 {
         if !check_channel(&wordle_map, msg) {
-            return send_embed_message(ctx, msg, GUESS_WRONG_CHANNEL_MSG).await;
+            return send_embed_message(ctx, msg, &catalog, GUESS_WRONG_CHANNEL_MSG).await;
         }
         if !wordle_map.joined_people.contains(&msg.author.id) {
-            return send_embed_message(ctx, msg, NOT_IN_GROUP_MSG).await;
+            return send_embed_message(ctx, msg, &catalog, NOT_IN_GROUP_MSG).await;
         }
         author = wordle_map.joined_people[0];
     }
 
-    let wordle = wordle_map.games.remove(&(msg.channel_id, author));
-    if wordle.is_none() {
-        return send_embed_message(ctx, msg, START_PLAYING_MSG).await;
-    }
+    let (wordle, started) = match wordle_map.games.remove(&(msg.channel_id, author)) {
+        Some(game) => game,
+        None => return reply_no_game(ctx, msg, &wordle_map, &catalog, author).await,
+    };
+    /* Players of a group have to be taken before the group is cleaned. */
+    let players = get_players(&mut wordle_map, author);
     clean_joined_and_max_playing(&mut wordle_map);
 
-    let wordle = &wordle.unwrap().0;
+    let wordle = &wordle;
+    wordle_map
+        .history
+        .record(wordle, msg.channel_id, &players, Outcome::GaveUp, started);
     send_wordle_solution(
         wordle,
         &msg.channel_id,
-        get_players(&mut wordle_map, author),
+        players.clone(),
+        &ctx.http,
+        &catalog,
+        &wordle_map.dictionary_url,
+    )
+    .await;
+    send_share_grid(
+        &ctx.http,
+        &msg.channel_id,
+        wordle_map.settings.results_channel(wordle.guild_id, author),
+        wordle,
+        false,
+        players,
+        &catalog,
+    )
+    .await;
+    spawn_wrap_up(
         &ctx.http,
+        &wordle_map.words,
+        &wordle_map.settings,
+        wordle,
+        msg.channel_id,
+        &catalog,
+    );
+    finish_challenge(
+        &ctx.http,
+        &mut wordle_map,
+        wordle.thread_parent.unwrap_or(msg.channel_id),
+        author,
+        wordle.challenge_setter,
+        wordle.guild_id,
+        Outcome::GaveUp,
     )
     .await;
     Ok(())
 }
 
+/* Replies to an invalid guess, unless the author keeps sending them,
+ * so that spamming invalid guesses doesn't flood the channel with replies. */
+async fn reject_guess(
+    ctx: &Context,
+    msg: &Message,
+    wordle_map: &mut MutexGuard<'_, ServerMap>,
+    catalog: &Catalog,
+    message: &str,
+) -> CommandResult {
+    if wordle_map.limiter.invalid_guess(msg.author.id) {
+        send_embed_message(ctx, msg, catalog, message).await
+    } else {
+        debug!("Ignored an invalid guess");
+        Ok(())
+    }
+}
+
 #[command]
 async fn guess(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
     let mut wordle_data = ctx.data.write().await;
@@ -297,125 +1192,1377 @@ async fn guess(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
         .expect("Failed to retrieve wordle map!")
         .lock()
         .await;
+    let catalog = wordle_map.user_catalog(msg.author.id, msg.guild_id);
 
-    check_ended_games(&mut wordle_map);
-
-    let mut author = msg.author.id;
-    let players = get_players(&mut wordle_map, author);
+    end_timed_out_games(&ctx.http, &mut wordle_map);
 
-    /* Check if a person can guess if there is a group game. */
-    if wordle_map.max_people_playing > 1 {
-        if wordle_map.joined_people.len() != wordle_map.max_people_playing {
-            return send_embed_message(
-                ctx,
-                msg,
-                &format!(
-                    "To start the game wait for {} other people",
-                    wordle_map.max_people_playing - wordle_map.joined_people.len()
-                ),
-            )
-            .await;
-        }
-        if !check_channel(&wordle_map, msg) {
-            return send_embed_message(ctx, msg, GUESS_WRONG_CHANNEL_MSG).await;
-        }
-        if !wordle_map.joined_people.contains(&msg.author.id) {
-            return send_embed_message(ctx, msg, NOT_IN_GROUP_MSG).await;
-        }
-        author = wordle_map.joined_people[0];
-    }
+    let players = get_players(&mut wordle_map, msg.author.id);
+    let dictionary_url = wordle_map.dictionary_url.clone();
 
-    let words_vector: Vec<String> = wordle_map
-        .words
-        .words
-        .iter()
-        .map(|word| word.word.clone())
-        .collect();
+    let author = match game_author(&wordle_map, msg, &catalog) {
+        Ok(author) => author,
+        Err(why) => return send_embed_text(ctx, msg, &catalog, &why).await,
+    };
 
     /* Word comparison is case insensitive. */
-    let guess = args.single_quoted::<String>()?.to_uppercase();
+    let guess = normalize(&args.single_quoted::<String>()?);
     let mut string_response = Builder::default();
 
-    if guess.len() != DEFAULT_SIZE || !guess.chars().all(char::is_alphabetic) {
-        return send_embed_message(ctx, msg, INCORRECT_GUESS_MSG).await;
-    }
-    if !words_vector.contains(&guess) {
-        return send_embed_message(ctx, msg, NOT_IN_LIST_MSG).await;
+    if !is_correct_word(&guess) {
+        return reject_guess(ctx, msg, &mut wordle_map, &catalog, INCORRECT_GUESS_MSG).await;
     }
 
-    let wordle = wordle_map.games.get_mut(&(msg.channel_id, author));
-    if wordle.is_none() {
-        return send_embed_message(ctx, msg, START_PLAYING_MSG).await;
+    /* Games played with a word pack or in another language may accept different guesses. */
+    let (pack, lang, follows_hints) = match wordle_map.games.get(&(msg.channel_id, author)) {
+        Some((w, _)) => (
+            w.pack.clone(),
+            w.lang.clone(),
+            !w.rules.hard_mode || w.follows_hints(&guess),
+        ),
+        None => return reply_no_game(ctx, msg, &wordle_map, &catalog, author).await,
+    };
+    if !wordle_map
+        .words
+        .is_allowed_guess(&guess, pack.as_deref(), lang.as_deref())
+    {
+        return reject_guess(ctx, msg, &mut wordle_map, &catalog, NOT_IN_LIST_MSG).await;
+    }
+    if !follows_hints {
+        return reject_guess(ctx, msg, &mut wordle_map, &catalog, HARD_MODE_MSG).await;
     }
+    wordle_map.limiter.valid_guess(msg.author.id);
 
-    let mut wordle = &mut wordle.unwrap().0;
+    let map = &mut *wordle_map;
+    let (wordle, started) = map.games.get_mut(&(msg.channel_id, author)).unwrap();
+    let started = *started;
+    /* Guessing resumes a paused game. */
+    wordle.resume();
     wordle.guesses += 1;
+    wordle.add_fields(guess.clone());
+    METRICS.guesses.inc();
+    wordle
+        .span
+        .in_scope(|| debug!(guesses = wordle.guesses, player = msg.author.id.0, "Guess"));
+    let setter = wordle.challenge_setter;
+    let challenge_channel = wordle.thread_parent.unwrap_or(msg.channel_id);
+    let finished = guess == wordle.word || wordle.guesses == wordle.rules.guesses;
+    let time_left = (!finished).then(|| wordle.time_left(started));
+    update_board(
+        &ctx.http,
+        &msg.channel_id,
+        wordle,
+        players.clone(),
+        time_left,
+        &catalog,
+    )
+    .await;
+    acknowledge_guess(&ctx.http, msg).await;
 
     /* Processing and saving the guess, then sending a reply to the same channel the guess was sent to. */
     if guess.eq(&wordle.word) {
         /* The guess was entirely correct */
-        string_response.append(WON_MSG);
-        if let Err(why) = send_message(WON_MSG, Some(players), &ctx.http, &msg.channel_id).await {
-            println!("Error sending the message: {}", why);
-        }
-        clean_game(&mut wordle_map, msg, author);
-    } else if wordle.guesses == GUESSES {
-        /* The player ran out of guesses. */
-        if let Err(why) = send_message(TOO_MANY_GUESSES_MSG, None, &ctx.http, &msg.channel_id)
-            .and_then(|_| async move {
-                send_wordle_solution(wordle, &msg.channel_id, players, &ctx.http).await;
-                Ok(())
-            })
-            .await
+        string_response.append(catalog.get(WON_MSG));
+        let id = map
+            .history
+            .record(wordle, msg.channel_id, &players, Outcome::Won, started);
+        let points = award_points(
+            &mut map.scores,
+            &map.words,
+            &map.settings,
+            wordle,
+            id,
+            &players,
+            started,
+        );
+        let text = format!(
+            "{} {}",
+            catalog.get(WON_MSG),
+            catalog.format(POINTS_EARNED_MSG, &[("points", points.to_string())])
+        );
+        if let Err(why) =
+            send_message(&text, Some(players.clone()), &ctx.http, &msg.channel_id).await
         {
-            println!("Error sending the message: {}", why);
+            error!(error = %why, "Error sending the message");
+            METRICS.api_errors.inc();
+        }
+        let unlocked = unlock_achievements(
+            &mut map.achievements,
+            &map.history,
+            wordle,
+            id,
+            &players,
+            msg.author.id,
+        );
+        if !unlocked.is_empty() {
+            let text = unlocked_achievements(&unlocked, &catalog);
+            if let Err(why) = send_message(&text, None, &ctx.http, &msg.channel_id).await {
+                error!(error = %why, "Error sending the message");
+                METRICS.api_errors.inc();
+            }
         }
+        send_share_grid(
+            &ctx.http,
+            &msg.channel_id,
+            map.settings.results_channel(wordle.guild_id, author),
+            wordle,
+            true,
+            players,
+            &catalog,
+        )
+        .await;
+        spawn_wrap_up(
+            &ctx.http,
+            &map.words,
+            &map.settings,
+            wordle,
+            msg.channel_id,
+            &catalog,
+        );
         clean_game(&mut wordle_map, msg, author);
-    } else {
-        /* Other cases. */
-        wordle.add_fields(guess);
-        if let Err(why) = send_string(
+        finish_challenge(
+            &ctx.http,
+            &mut wordle_map,
+            challenge_channel,
+            author,
+            setter,
+            msg.guild_id,
+            Outcome::Won,
+        )
+        .await;
+    } else if wordle.guesses == wordle.rules.guesses {
+        /* The player ran out of guesses. */
+        map.history
+            .record(wordle, msg.channel_id, &players, Outcome::Lost, started);
+        if let Err(why) = send_message(
+            catalog.get(TOO_MANY_GUESSES_MSG),
+            None,
             &ctx.http,
             &msg.channel_id,
-            display_wordle(wordle, players).as_str(),
         )
-        .and_then(|message| async move {
-            react_to_message(&ctx.http, &message, wordle).await;
-            Ok(())
-        })
         .await
         {
-            println!("Error sending the message: {}", why);
+            error!(error = %why, "Error sending the message");
+            METRICS.api_errors.inc();
         }
+        send_wordle_solution(
+            wordle,
+            &msg.channel_id,
+            players.clone(),
+            &ctx.http,
+            &catalog,
+            &dictionary_url,
+        )
+        .await;
+        send_share_grid(
+            &ctx.http,
+            &msg.channel_id,
+            map.settings.results_channel(wordle.guild_id, author),
+            wordle,
+            false,
+            players,
+            &catalog,
+        )
+        .await;
+        spawn_wrap_up(
+            &ctx.http,
+            &map.words,
+            &map.settings,
+            wordle,
+            msg.channel_id,
+            &catalog,
+        );
+        clean_game(&mut wordle_map, msg, author);
+        finish_challenge(
+            &ctx.http,
+            &mut wordle_map,
+            challenge_channel,
+            author,
+            setter,
+            msg.guild_id,
+            Outcome::Lost,
+        )
+        .await;
     }
     Ok(())
 }
 
-/* Declaration of a set of available commands. */
-#[group("public")]
-#[commands(start, guess, help, join, giveup)]
-struct Public;
+/* Stops the clock of the author's game until `!resume` or the next guess.
+ * Games can only be paused for a limited time in total. */
+#[command]
+async fn pause(ctx: &Context, msg: &Message) -> CommandResult {
+    let mut wordle_data = ctx.data.write().await;
+    let mut wordle_map = wordle_data
+        .get_mut::<ServerKey>()
+        .expect("Failed to retrieve wordle map!")
+        .lock()
+        .await;
+    let catalog = wordle_map.user_catalog(msg.author.id, msg.guild_id);
 
-#[tokio::main]
-async fn main() {
-    let config = Config::load().unwrap();
-    let mut client = ClientBuilder::new(
-        config.token(),
-        GatewayIntents::GUILD_MESSAGES
-            .union(GatewayIntents::MESSAGE_CONTENT)
-            .union(GatewayIntents::GUILD_MESSAGE_REACTIONS),
-    )
-    .event_handler(Handler)
-    .framework(
-        StandardFramework::new()
-            .configure(|c| c.with_whitespace(true).prefix(config.prefix()))
-            .group(&PUBLIC_GROUP),
+    end_timed_out_games(&ctx.http, &mut wordle_map);
+
+    let author = match game_author(&wordle_map, msg, &catalog) {
+        Ok(author) => author,
+        Err(why) => return send_embed_text(ctx, msg, &catalog, &why).await,
+    };
+    let players = get_players(&mut wordle_map, author);
+    let (wordle, started) = match wordle_map.games.get_mut(&(msg.channel_id, author)) {
+        Some((w, started)) => (w, *started),
+        None => return reply_no_game(ctx, msg, &wordle_map, &catalog, author).await,
+    };
+    if wordle.is_paused() {
+        return send_embed_message(ctx, msg, &catalog, ALREADY_PAUSED_MSG).await;
+    }
+    let left = wordle.pause_left();
+    if left == 0 {
+        return send_embed_message(ctx, msg, &catalog, NO_PAUSE_LEFT_MSG).await;
+    }
+    wordle.pause();
+    wordle
+        .span
+        .in_scope(|| info!(pause_left = left, "Game paused"));
+    if wordle.board_message_id.is_some() {
+        let time_left = Some(wordle.time_left(started));
+        update_board(
+            &ctx.http,
+            &msg.channel_id,
+            wordle,
+            players,
+            time_left,
+            &catalog,
+        )
+        .await;
+    }
+    let text = catalog.format(PAUSED_MSG, &[("seconds", left.to_string())]);
+    send_embed_text(ctx, msg, &catalog, &text).await
+}
+
+/* Starts the clock of the author's paused game again. */
+#[command]
+async fn resume(ctx: &Context, msg: &Message) -> CommandResult {
+    let mut wordle_data = ctx.data.write().await;
+    let mut wordle_map = wordle_data
+        .get_mut::<ServerKey>()
+        .expect("Failed to retrieve wordle map!")
+        .lock()
+        .await;
+    let catalog = wordle_map.user_catalog(msg.author.id, msg.guild_id);
+
+    end_timed_out_games(&ctx.http, &mut wordle_map);
+
+    let author = match game_author(&wordle_map, msg, &catalog) {
+        Ok(author) => author,
+        Err(why) => return send_embed_text(ctx, msg, &catalog, &why).await,
+    };
+    let players = get_players(&mut wordle_map, author);
+    let (wordle, started) = match wordle_map.games.get_mut(&(msg.channel_id, author)) {
+        Some((w, started)) => (w, *started),
+        None => return reply_no_game(ctx, msg, &wordle_map, &catalog, author).await,
+    };
+    if !wordle.is_paused() {
+        return send_embed_message(ctx, msg, &catalog, NOT_PAUSED_MSG).await;
+    }
+    wordle.resume();
+    wordle.span.in_scope(|| info!("Game resumed"));
+    let left = wordle.time_left(started);
+    if wordle.board_message_id.is_some() {
+        update_board(
+            &ctx.http,
+            &msg.channel_id,
+            wordle,
+            players,
+            Some(left),
+            &catalog,
+        )
+        .await;
+    }
+    let text = catalog.format(RESUMED_MSG, &[("seconds", left.to_string())]);
+    send_embed_text(ctx, msg, &catalog, &text).await
+}
+
+/* Tells how many words can still be the answer of a game and suggests the guess
+ * that narrows them down the most. Every hint is counted on the game. */
+#[command]
+async fn hint(ctx: &Context, msg: &Message) -> CommandResult {
+    let (catalog, guesses, candidates) = {
+        let mut wordle_data = ctx.data.write().await;
+        let mut wordle_map = wordle_data
+            .get_mut::<ServerKey>()
+            .expect("Failed to retrieve wordle map!")
+            .lock()
+            .await;
+        let catalog = wordle_map.user_catalog(msg.author.id, msg.guild_id);
+
+        end_timed_out_games(&ctx.http, &mut wordle_map);
+
+        let author = match game_author(&wordle_map, msg, &catalog) {
+            Ok(author) => author,
+            Err(why) => return send_embed_text(ctx, msg, &catalog, &why).await,
+        };
+        let map = &mut *wordle_map;
+        let wordle = match map.games.get_mut(&(msg.channel_id, author)) {
+            Some((w, _)) => w,
+            None => return reply_no_game(ctx, msg, map, &catalog, author).await,
+        };
+        wordle.hints += 1;
+
+        let pack = wordle.pack.as_deref();
+        let lang = wordle.lang.as_deref();
+        let candidates = solver::candidates(&map.words.answers(pack, lang), &wordle.fields);
+        /* In hard mode only guesses following the hints can be suggested. */
+        let guesses: Vec<Vec<char>> = map
+            .words
+            .guess_list(pack, lang)
+            .into_iter()
+            .filter(|guess| !wordle.rules.hard_mode || wordle.follows_hints(guess))
+            .map(|guess| guess.chars().collect())
+            .collect();
+        (catalog, guesses, candidates)
+    };
+
+    /* Scoring every guess takes a while, so it's done without blocking other commands. */
+    let count = candidates.len();
+    let best =
+        tokio::task::spawn_blocking(move || solver::best_guess(&guesses, &candidates)).await?;
+    match best {
+        Some((guess, _)) => {
+            let hint = catalog.format(
+                HINT_MSG,
+                &[
+                    ("candidates", count.to_string()),
+                    ("guess", guess.into_iter().collect()),
+                ],
+            );
+            send_embed_text(ctx, msg, &catalog, &hint).await
+        }
+        None => send_embed_message(ctx, msg, &catalog, NO_CANDIDATES_MSG).await,
+    }
+}
+
+/* Lists the latest games played on the server or by the mentioned player.
+ * In direct messages only the author's own games are listed, as they are played privately. */
+#[command]
+async fn history(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let wordle_data = ctx.data.read().await;
+    let wordle_map = wordle_data
+        .get::<ServerKey>()
+        .expect("Failed to retrieve wordle map!")
+        .lock()
+        .await;
+    let catalog = wordle_map.user_catalog(msg.author.id, msg.guild_id);
+
+    let player = match msg.guild_id {
+        Some(_) => args.single::<UserId>().ok(),
+        None => Some(msg.author.id),
+    };
+    let games = wordle_map
+        .history
+        .recent(msg.guild_id, player, RECENT_GAMES);
+    if games.is_empty() {
+        return send_embed_message(ctx, msg, &catalog, NO_HISTORY_MSG).await;
+    }
+    send_embed_text(ctx, msg, &catalog, &history_list(&games, &catalog)).await
+}
+
+/* Displays the board of a finished game, e.g. `!replay 12`. */
+#[command]
+async fn replay(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let wordle_data = ctx.data.read().await;
+    let wordle_map = wordle_data
+        .get::<ServerKey>()
+        .expect("Failed to retrieve wordle map!")
+        .lock()
+        .await;
+    let catalog = wordle_map.user_catalog(msg.author.id, msg.guild_id);
+
+    let id = match args
+        .single::<String>()
+        .map(|id| id.trim_start_matches('#').parse::<u64>())
+    {
+        Ok(Ok(id)) => id,
+        _ => return send_embed_message(ctx, msg, &catalog, REPLAY_USAGE_MSG).await,
+    };
+    /* Games played on other servers are not shown, nor games of other players in direct messages. */
+    match wordle_map.history.get(id).filter(|game| {
+        game.guild == msg.guild_id.map(|g| g.0)
+            && (msg.guild_id.is_some() || game.players.contains(&msg.author.id.0))
+    }) {
+        Some(game) => send_embed_text(ctx, msg, &catalog, &display_replay(game, &catalog)).await,
+        None => send_embed_message(ctx, msg, &catalog, NO_GAME_RECORD_MSG).await,
+    }
+}
+
+/* Lists the players with the most points on the server in the current season, e.g. `!leaderboard`,
+ * in a past one, e.g. `!leaderboard 2024-03`, or of all time with `!leaderboard all`.
+ * `!leaderboard rating` lists the highest rated players instead. */
+#[command]
+async fn leaderboard(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let wordle_data = ctx.data.read().await;
+    let wordle_map = wordle_data
+        .get::<ServerKey>()
+        .expect("Failed to retrieve wordle map!")
+        .lock()
+        .await;
+    let catalog = wordle_map.user_catalog(msg.author.id, msg.guild_id);
+
+    let season = match args.single::<String>() {
+        Err(_) => Some(Season::current()),
+        Ok(arg) if arg == "all" => None,
+        Ok(arg) if arg == "rating" => {
+            let mut ranking = msg
+                .guild_id
+                .map(|guild| wordle_map.ratings.ranking(guild))
+                .unwrap_or_default();
+            if ranking.is_empty() {
+                return send_embed_message(ctx, msg, &catalog, NO_RATINGS_MSG).await;
+            }
+            ranking.truncate(LEADERBOARD_SIZE);
+            let text = rating_list(&ranking, &catalog);
+            return send_embed_text(ctx, msg, &catalog, &text).await;
+        }
+        Ok(arg) => match arg.parse::<Season>() {
+            Ok(season) => Some(season),
+            Err(_) => return send_embed_message(ctx, msg, &catalog, LEADERBOARD_USAGE_MSG).await,
+        },
+    };
+    let standings = wordle_map
+        .scores
+        .leaderboard(msg.guild_id, season, LEADERBOARD_SIZE);
+    if standings.is_empty() {
+        return send_embed_message(ctx, msg, &catalog, NO_SCORES_MSG).await;
+    }
+    let text = leaderboard_list(&standings, season, &catalog);
+    send_embed_text(ctx, msg, &catalog, &text).await
+}
+
+/* Shows points, wins and achievements of the author or the mentioned player, e.g. `!profile @player`. */
+#[command]
+async fn profile(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let wordle_data = ctx.data.read().await;
+    let wordle_map = wordle_data
+        .get::<ServerKey>()
+        .expect("Failed to retrieve wordle map!")
+        .lock()
+        .await;
+    let catalog = wordle_map.user_catalog(msg.author.id, msg.guild_id);
+
+    let player = args.single::<UserId>().unwrap_or(msg.author.id);
+    let games = wordle_map
+        .history
+        .recent(msg.guild_id, Some(player), usize::MAX);
+    let wins = games
+        .iter()
+        .filter(|game| game.outcome == Outcome::Won)
+        .count();
+    let season = Season::current();
+    let scores = &wordle_map.scores;
+    let achievements: Vec<Achievement> = wordle_map
+        .achievements
+        .of(player)
+        .iter()
+        .map(|unlocked| unlocked.achievement)
+        .collect();
+    let values = [
+        ("player", player.0.to_string()),
+        ("season", season.to_string()),
+        (
+            "season_points",
+            scores.total(player, msg.guild_id, Some(season)).to_string(),
+        ),
+        (
+            "points",
+            scores.total(player, msg.guild_id, None).to_string(),
+        ),
+        ("wins", wins.to_string()),
+        ("games", games.len().to_string()),
+        ("streak", wordle_map.history.streak(player).to_string()),
+        ("unlocked", achievements.len().to_string()),
+        ("total", Achievement::ALL.len().to_string()),
+        ("achievements", achievement_list(&achievements, &catalog)),
+    ];
+    send_embed_text(ctx, msg, &catalog, &catalog.format(PROFILE_MSG, &values)).await
+}
+
+/* Shows the rating of the author or the mentioned player on the server, e.g. `!rating @player`. */
+#[command]
+#[only_in(guilds)]
+async fn rating(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let wordle_data = ctx.data.read().await;
+    let wordle_map = wordle_data
+        .get::<ServerKey>()
+        .expect("Failed to retrieve wordle map!")
+        .lock()
+        .await;
+    let catalog = wordle_map.user_catalog(msg.author.id, msg.guild_id);
+
+    let guild = msg.guild_id.expect("The command is only used on servers");
+    let player = args.single::<UserId>().unwrap_or(msg.author.id);
+    let ranking = wordle_map.ratings.ranking(guild);
+    let place = match ranking.iter().position(|(p, _)| *p == player) {
+        Some(place) => place,
+        None => {
+            let text = catalog.format(NO_RATING_MSG, &[("player", player.0.to_string())]);
+            return send_embed_text(ctx, msg, &catalog, &text).await;
+        }
+    };
+    let rating = ranking[place].1;
+    let values = [
+        ("player", player.0.to_string()),
+        ("rating", format!("{:.0}", rating.rating)),
+        ("games", rating.games.to_string()),
+        ("place", (place + 1).to_string()),
+        ("players", ranking.len().to_string()),
+    ];
+    send_embed_text(ctx, msg, &catalog, &catalog.format(RATING_MSG, &values)).await
+}
+
+/* Chooses the server results of the author's games in direct messages are announced on,
+ * e.g. `!home` sent on the server, or stops announcing them with `!home off`. */
+#[command]
+async fn home(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let mut wordle_data = ctx.data.write().await;
+    let mut wordle_map = wordle_data
+        .get_mut::<ServerKey>()
+        .expect("Failed to retrieve wordle map!")
+        .lock()
+        .await;
+    let catalog = wordle_map.user_catalog(msg.author.id, msg.guild_id);
+
+    if args
+        .single::<String>()
+        .is_ok_and(|arg| arg.eq_ignore_ascii_case("off"))
+    {
+        wordle_map.settings.set_home(msg.author.id, None);
+        return send_embed_message(ctx, msg, &catalog, HOME_CLEARED_MSG).await;
+    }
+    let guild = match msg.guild_id {
+        Some(guild) => guild,
+        None => return send_embed_message(ctx, msg, &catalog, HOME_USAGE_MSG).await,
+    };
+    let home = Home {
+        guild: guild.0,
+        channel: msg.channel_id.0,
+    };
+    wordle_map.settings.set_home(msg.author.id, Some(home));
+    send_embed_message(ctx, msg, &catalog, HOME_SET_MSG).await
+}
+
+/* Lets a player set a word for others to guess on a given channel.
+ * The command is sent in a direct message, so that the word stays secret. */
+#[command]
+#[only_in(dms)]
+async fn challenge(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let mut wordle_data = ctx.data.write().await;
+    let mut wordle_map = wordle_data
+        .get_mut::<ServerKey>()
+        .expect("Failed to retrieve wordle map!")
+        .lock()
+        .await;
+    let catalog = wordle_map.user_catalog(msg.author.id, None);
+
+    let channel = match args.single::<ChannelId>() {
+        Ok(c) => c,
+        Err(_) => return send_embed_message(ctx, msg, &catalog, CHALLENGE_USAGE_MSG).await,
+    };
+    let word = match args.single_quoted::<String>() {
+        Ok(w) => normalize(&w),
+        Err(_) => return send_embed_message(ctx, msg, &catalog, CHALLENGE_USAGE_MSG).await,
+    };
+
+    if !is_correct_word(&word) {
+        return send_embed_message(ctx, msg, &catalog, CHALLENGE_WORD_MSG).await;
+    }
+    if !wordle_map.words.contains(&word) {
+        return send_embed_message(ctx, msg, &catalog, NOT_IN_LIST_MSG).await;
+    }
+    let guild = match channel.to_channel(ctx).await {
+        Ok(Channel::Guild(c)) => c.guild_id,
+        _ => return send_embed_message(ctx, msg, &catalog, CHALLENGE_CHANNEL_MSG).await,
+    };
+    /* Channels of servers the setter isn't on and channels the bot doesn't play on
+     * are treated as if they didn't exist. */
+    if guild.member(ctx, msg.author.id).await.is_err()
+        || !wordle_map
+            .settings
+            .guild(Some(guild))
+            .allows_channel(channel)
+    {
+        return send_embed_message(ctx, msg, &catalog, CHALLENGE_CHANNEL_MSG).await;
+    }
+    let taken = wordle_map
+        .challenges
+        .get(&channel)
+        .is_some_and(|c| c.setter != msg.author.id && !c.is_finished());
+    if taken {
+        return send_embed_message(ctx, msg, &catalog, CHALLENGE_TAKEN_MSG).await;
+    }
+
+    /* A new challenge replaces the previous one set on the channel. */
+    wordle_map
+        .challenges
+        .insert(channel, Challenge::new(word, msg.author.id));
+    let channel_catalog = wordle_map.guild_catalog(Some(guild));
+    send_challenge_announcement(&ctx.http, &channel, msg.author.id, &channel_catalog).await;
+    send_embed_message(ctx, msg, &catalog, CHALLENGE_SET_MSG).await
+}
+
+/* Lets a user choose the language of bot's messages sent to them. */
+#[command]
+async fn locale(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let mut wordle_data = ctx.data.write().await;
+    let mut wordle_map = wordle_data
+        .get_mut::<ServerKey>()
+        .expect("Failed to retrieve wordle map!")
+        .lock()
+        .await;
+
+    let code = args.single::<String>().map(|c| c.to_lowercase());
+    match code {
+        Ok(code) if wordle_map.locales.contains(&code) => {
+            wordle_map.locales.set_user_locale(msg.author.id, code);
+            let catalog = wordle_map.user_catalog(msg.author.id, msg.guild_id);
+            send_embed_message(ctx, msg, &catalog, LOCALE_SET_MSG).await
+        }
+        _ => {
+            let catalog = wordle_map.user_catalog(msg.author.id, msg.guild_id);
+            let usage = catalog.format(
+                LOCALE_USAGE_MSG,
+                &[("locales", wordle_map.locales.codes().join(", "))],
+            );
+            send_embed_text(ctx, msg, &catalog, &usage).await
+        }
+    }
+}
+
+/* Lets an administrator choose the default language of bot's messages on a server. */
+#[command]
+#[only_in(guilds)]
+#[required_permissions(ADMINISTRATOR)]
+async fn serverlocale(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let mut wordle_data = ctx.data.write().await;
+    let mut wordle_map = wordle_data
+        .get_mut::<ServerKey>()
+        .expect("Failed to retrieve wordle map!")
+        .lock()
+        .await;
+    let guild = msg
+        .guild_id
+        .expect("Server command used outside of a server!");
+
+    let code = args.single::<String>().map(|c| c.to_lowercase());
+    match code {
+        Ok(code) if wordle_map.locales.contains(&code) => {
+            wordle_map.settings.guild_mut(guild).locale = Some(code);
+            wordle_map.settings.save();
+            let catalog = wordle_map.guild_catalog(Some(guild));
+            send_embed_message(ctx, msg, &catalog, SERVER_LOCALE_SET_MSG).await
+        }
+        _ => {
+            let catalog = wordle_map.user_catalog(msg.author.id, msg.guild_id);
+            let usage = catalog.format(
+                SERVER_LOCALE_USAGE_MSG,
+                &[("locales", wordle_map.locales.codes().join(", "))],
+            );
+            send_embed_text(ctx, msg, &catalog, &usage).await
+        }
+    }
+}
+
+/* Describes current settings of a server, showing default values for the ones that are not set. */
+fn describe_settings(wordle_map: &ServerMap, guild: GuildId, catalog: &Catalog) -> String {
+    let settings = wordle_map.settings.guild(Some(guild));
+    let rules = settings.rules(wordle_map.rules);
+    let channels = if settings.channels.is_empty() {
+        String::from("all")
+    } else {
+        settings
+            .channels
+            .iter()
+            .map(|c| format!("<#{}>", c))
+            .collect::<Vec<String>>()
+            .join(", ")
+    };
+    catalog.format(
+        CONFIG_MSG,
+        &[
+            ("guesses", rules.guesses.to_string()),
+            ("time", rules.time_limit.to_string()),
+            (
+                "hard",
+                String::from(if rules.hard_mode { "on" } else { "off" }),
+            ),
+            (
+                "analysis",
+                String::from(if settings.analysis { "on" } else { "off" }),
+            ),
+            (
+                "pack",
+                settings
+                    .pack
+                    .clone()
+                    .unwrap_or_else(|| String::from("none")),
+            ),
+            (
+                "lang",
+                settings.lang.clone().unwrap_or_else(|| String::from("en")),
+            ),
+            (
+                "locale",
+                settings
+                    .locale
+                    .clone()
+                    .unwrap_or_else(|| String::from(DEFAULT_LOCALE)),
+            ),
+            ("channels", channels),
+            (
+                "results",
+                settings
+                    .results_channel
+                    .map(|c| format!("<#{}>", c))
+                    .unwrap_or_else(|| String::from("none")),
+            ),
+            (
+                "modrole",
+                settings
+                    .moderator_role
+                    .map(|r| format!("<@&{}>", r))
+                    .unwrap_or_else(|| String::from("none")),
+            ),
+        ],
+    )
+}
+
+/* Parses a positive number given as a value of a setting. */
+fn parse_positive<T: std::str::FromStr + Default + PartialEq>(
+    value: &str,
+) -> Result<T, &'static str> {
+    match value.parse::<T>() {
+        Ok(number) if number != T::default() => Ok(number),
+        _ => Err(INVALID_SETTING_MSG),
+    }
+}
+
+/* Changes a single setting of a server, `default` restores the value from the config file.
+ * Returns the name of a message explaining the mistake if the value is incorrect. */
+fn update_setting(
+    wordle_map: &mut ServerMap,
+    guild: GuildId,
+    key: &str,
+    value: &str,
+) -> Result<(), &'static str> {
+    let reset = value == "default";
+    match key {
+        "guesses" => {
+            let guesses = if reset {
+                None
+            } else {
+                Some(parse_positive(value)?)
+            };
+            wordle_map.settings.guild_mut(guild).guesses = guesses;
+        }
+        "time" => {
+            let time_limit = if reset {
+                None
+            } else {
+                Some(parse_positive(value)?)
+            };
+            wordle_map.settings.guild_mut(guild).time_limit = time_limit;
+        }
+        "hard" => {
+            wordle_map.settings.guild_mut(guild).hard_mode = match value {
+                "on" => true,
+                "off" | "default" => false,
+                _ => return Err(INVALID_SETTING_MSG),
+            };
+        }
+        "analysis" => {
+            wordle_map.settings.guild_mut(guild).analysis = match value {
+                "on" => true,
+                "off" | "default" => false,
+                _ => return Err(INVALID_SETTING_MSG),
+            };
+        }
+        /* A game can't use both a pack and a language, so choosing one clears the other. */
+        "pack" => {
+            let pack = value.to_lowercase();
+            if reset || pack == "none" {
+                wordle_map.settings.guild_mut(guild).pack = None;
+            } else if wordle_map.words.packs.contains_key(&pack) {
+                let settings = wordle_map.settings.guild_mut(guild);
+                settings.pack = Some(pack);
+                settings.lang = None;
+            } else {
+                return Err(INVALID_SETTING_MSG);
+            }
+        }
+        "lang" => {
+            let lang = value.to_lowercase();
+            if reset || lang == "en" {
+                wordle_map.settings.guild_mut(guild).lang = None;
+            } else if wordle_map.words.languages.contains_key(&lang) {
+                let settings = wordle_map.settings.guild_mut(guild);
+                settings.lang = Some(lang);
+                settings.pack = None;
+            } else {
+                return Err(INVALID_SETTING_MSG);
+            }
+        }
+        "locale" => {
+            let locale = value.to_lowercase();
+            if reset {
+                wordle_map.settings.guild_mut(guild).locale = None;
+            } else if wordle_map.locales.contains(&locale) {
+                wordle_map.settings.guild_mut(guild).locale = Some(locale);
+            } else {
+                return Err(INVALID_SETTING_MSG);
+            }
+        }
+        "channels" => {
+            let channels = if reset || value == "all" {
+                Vec::new()
+            } else {
+                value
+                    .split_whitespace()
+                    .map(|c| c.parse::<ChannelId>().map(|c| c.0))
+                    .collect::<Result<Vec<u64>, _>>()
+                    .map_err(|_| INVALID_SETTING_MSG)?
+            };
+            wordle_map.settings.guild_mut(guild).channels = channels;
+        }
+        "results" => {
+            let channel = if reset || value == "none" {
+                None
+            } else {
+                let channel = value
+                    .parse::<ChannelId>()
+                    .map_err(|_| INVALID_SETTING_MSG)?;
+                Some(channel.0)
+            };
+            wordle_map.settings.guild_mut(guild).results_channel = channel;
+        }
+        "prefix" => {
+            if value.is_empty() || value.contains(char::is_whitespace) {
+                return Err(INVALID_SETTING_MSG);
+            }
+            wordle_map.settings.guild_mut(guild).prefix =
+                if reset { None } else { Some(value.to_string()) };
+        }
+        "modrole" => {
+            let role = if reset || value == "none" {
+                None
+            } else {
+                let role = value.parse::<RoleId>().map_err(|_| INVALID_SETTING_MSG)?;
+                Some(role.0)
+            };
+            wordle_map.settings.guild_mut(guild).moderator_role = role;
+        }
+        _ => return Err(UNKNOWN_SETTING_MSG),
+    }
+    wordle_map.settings.save();
+    Ok(())
+}
+
+/* Lets an administrator view and change settings of games on a server,
+ * e.g. `!config guesses 5` or `!config channels #wordle #games`. */
+#[command("config")]
+#[only_in(guilds)]
+#[required_permissions(ADMINISTRATOR)]
+async fn guild_config(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let mut wordle_data = ctx.data.write().await;
+    let mut wordle_map = wordle_data
+        .get_mut::<ServerKey>()
+        .expect("Failed to retrieve wordle map!")
+        .lock()
+        .await;
+    let guild = msg
+        .guild_id
+        .expect("Server command used outside of a server!");
+
+    let key = match args.single::<String>() {
+        Ok(key) => key.to_lowercase(),
+        Err(_) => {
+            let catalog = wordle_map.user_catalog(msg.author.id, msg.guild_id);
+            let description = describe_settings(&wordle_map, guild, &catalog);
+            return send_embed_text(ctx, msg, &catalog, &description).await;
+        }
+    };
+    let value = args.rest().trim();
+
+    let result = update_setting(&mut wordle_map, guild, &key, value);
+    /* The catalog is taken after the change, so a new locale is used right away. */
+    let catalog = wordle_map.user_catalog(msg.author.id, msg.guild_id);
+    match result {
+        Ok(()) => send_embed_message(ctx, msg, &catalog, CONFIG_SET_MSG).await,
+        Err(why) => send_embed_message(ctx, msg, &catalog, why).await,
+    }
+}
+
+/* Ends a game, e.g. on a moderator's request, and sends its solution to the players.
+ * If it was a group game, the group is removed as well. */
+async fn end_game(
+    http: &Arc<Http>,
+    wordle_map: &mut MutexGuard<'_, ServerMap>,
+    key: (ChannelId, UserId),
+) {
+    let (wordle, started) = match wordle_map.games.remove(&key) {
+        Some(game) => game,
+        None => return,
+    };
+    let (channel, player) = key;
+    let group =
+        wordle_map.max_people_playing > 1 && wordle_map.joined_people.first() == Some(&player);
+    let players = if group {
+        wordle_map.joined_people.clone()
+    } else {
+        vec![player]
+    };
+    if group {
+        clean_joined_and_max_playing(wordle_map);
+    }
+    wordle_map
+        .history
+        .record(&wordle, channel, &players, Outcome::Ended, started);
+
+    let catalog = wordle_map.guild_catalog(wordle.guild_id);
+    let dictionary_url = wordle_map.dictionary_url.clone();
+    if let Err(why) = send_message(
+        catalog.get(GAME_ENDED_BY_MODERATOR_MSG),
+        Some(players.clone()),
+        http,
+        &channel,
+    )
+    .await
+    {
+        error!(error = %why, "Error sending the message");
+        METRICS.api_errors.inc();
+    }
+    send_wordle_solution(&wordle, &channel, players, http, &catalog, &dictionary_url).await;
+    spawn_wrap_up(
+        http,
+        &wordle_map.words,
+        &wordle_map.settings,
+        &wordle,
+        channel,
+        &catalog,
+    );
+    finish_challenge(
+        http,
+        wordle_map,
+        wordle.thread_parent.unwrap_or(channel),
+        player,
+        wordle.challenge_setter,
+        wordle.guild_id,
+        Outcome::Ended,
+    )
+    .await;
+}
+
+/* Lets through people who can manage messages on the server
+ * and members of the moderator role chosen in its settings. */
+#[check]
+#[name = "Moderator"]
+async fn moderator_check(
+    ctx: &Context,
+    msg: &Message,
+    _: &mut Args,
+    _: &CommandOptions,
+) -> Result<(), Reason> {
+    let member = match msg.member(ctx).await {
+        Ok(member) => member,
+        Err(_) => return Err(Reason::Log(String::from("Failed to get the member"))),
+    };
+    if let Ok(permissions) = member.permissions(ctx) {
+        if permissions.administrator() || permissions.manage_messages() {
+            return Ok(());
+        }
+    }
+
+    let wordle_data = ctx.data.read().await;
+    let wordle_map = wordle_data
+        .get::<ServerKey>()
+        .expect("Failed to retrieve wordle map!")
+        .lock()
+        .await;
+    match wordle_map.settings.guild(msg.guild_id).moderator_role {
+        Some(role) if member.roles.contains(&RoleId(role)) => Ok(()),
+        _ => Err(Reason::User(String::from("Not a moderator"))),
+    }
+}
+
+/* Ends all games on a channel or the game of a player, e.g. `!endgame #wordle` or `!endgame @player`.
+ * Games in threads created on the channel are ended too.
+ * A game of a group can be ended by mentioning any of its players. */
+#[command]
+async fn endgame(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let mut wordle_data = ctx.data.write().await;
+    let mut wordle_map = wordle_data
+        .get_mut::<ServerKey>()
+        .expect("Failed to retrieve wordle map!")
+        .lock()
+        .await;
+    let catalog = wordle_map.user_catalog(msg.author.id, msg.guild_id);
+
+    let target = args.single::<String>().unwrap_or_default();
+    let in_group = |user: UserId| {
+        wordle_map.max_people_playing > 1 && wordle_map.joined_people.contains(&user)
+    };
+    let keys: Vec<(ChannelId, UserId)> = if let Some(channel) = parse_channel(&target) {
+        wordle_map
+            .games
+            .iter()
+            .filter(|((c, _), (w, _))| {
+                (c.0 == channel || w.thread_parent.map(|p| p.0) == Some(channel))
+                    && w.guild_id == msg.guild_id
+            })
+            .map(|(&key, _)| key)
+            .collect()
+    } else if let Some(user) = parse_username(&target) {
+        let user = UserId(user);
+        wordle_map
+            .games
+            .iter()
+            .filter(|((_, p), (w, _))| (*p == user || in_group(user)) && w.guild_id == msg.guild_id)
+            .map(|(&key, _)| key)
+            .collect()
+    } else {
+        return send_embed_message(ctx, msg, &catalog, ENDGAME_USAGE_MSG).await;
+    };
+
+    if keys.is_empty() {
+        return send_embed_message(ctx, msg, &catalog, NO_GAMES_FOUND_MSG).await;
+    }
+    let ended = keys.len();
+    for key in keys {
+        end_game(&ctx.http, &mut wordle_map, key).await;
+    }
+    send_embed_text(
+        ctx,
+        msg,
+        &catalog,
+        &catalog.format(GAMES_ENDED_MSG, &[("games", ended.to_string())]),
     )
-    .type_map_insert::<ServerKey>(Arc::new(Mutex::new(ServerMap::new().await)))
     .await
-    .expect("Couldn't create the new client!");
+}
+
+/* Removes the group gathering players or playing on the server, together with its game.
+ * Fixes a group that got stuck, e.g. because its game ran out of time before enough people joined. */
+#[command]
+async fn resetlobby(ctx: &Context, msg: &Message) -> CommandResult {
+    let mut wordle_data = ctx.data.write().await;
+    let mut wordle_map = wordle_data
+        .get_mut::<ServerKey>()
+        .expect("Failed to retrieve wordle map!")
+        .lock()
+        .await;
+    let catalog = wordle_map.user_catalog(msg.author.id, msg.guild_id);
+
+    if wordle_map.max_people_playing == 1 {
+        return send_embed_message(ctx, msg, &catalog, NO_LOBBY_MSG).await;
+    }
+    /* The game of a group is kept under the id of the person who started it. */
+    let starter = wordle_map.joined_people.first().copied();
+    let game = wordle_map
+        .games
+        .iter()
+        .find(|((_, p), _)| Some(*p) == starter)
+        .map(|(&key, (w, _))| (key, w.guild_id));
+    match game {
+        Some((_, guild)) if guild != msg.guild_id => {
+            return send_embed_message(ctx, msg, &catalog, NO_LOBBY_MSG).await;
+        }
+        Some((key, _)) => {
+            wordle_map.games.remove(&key);
+        }
+        None => {}
+    }
+    clean_joined_and_max_playing(&mut wordle_map);
+    send_embed_message(ctx, msg, &catalog, LOBBY_RESET_MSG).await
+}
+
+/* Bans a word, so that it's never picked as an answer on the server. Without a word lists banned words. */
+#[command]
+async fn banword(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let mut wordle_data = ctx.data.write().await;
+    let mut wordle_map = wordle_data
+        .get_mut::<ServerKey>()
+        .expect("Failed to retrieve wordle map!")
+        .lock()
+        .await;
+    let catalog = wordle_map.user_catalog(msg.author.id, msg.guild_id);
+    let guild = msg
+        .guild_id
+        .expect("Server command used outside of a server!");
+
+    let word = normalize(&args.single_quoted::<String>().unwrap_or_default());
+    if !is_correct_word(&word) {
+        let banned = &wordle_map.settings.guild(Some(guild)).banned_words;
+        let usage = catalog.format(BANWORD_USAGE_MSG, &[("words", banned.join(", "))]);
+        return send_embed_text(ctx, msg, &catalog, &usage).await;
+    }
+    let settings = wordle_map.settings.guild_mut(guild);
+    if !settings.banned_words.contains(&word) {
+        settings.banned_words.push(word);
+        wordle_map.settings.save();
+    }
+    send_embed_message(ctx, msg, &catalog, WORD_BANNED_MSG).await
+}
+
+/* Lets a banned word be picked as an answer again. */
+#[command]
+async fn unbanword(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let mut wordle_data = ctx.data.write().await;
+    let mut wordle_map = wordle_data
+        .get_mut::<ServerKey>()
+        .expect("Failed to retrieve wordle map!")
+        .lock()
+        .await;
+    let catalog = wordle_map.user_catalog(msg.author.id, msg.guild_id);
+    let guild = msg
+        .guild_id
+        .expect("Server command used outside of a server!");
+
+    let word = normalize(&args.single_quoted::<String>().unwrap_or_default());
+    let settings = wordle_map.settings.guild_mut(guild);
+    if !settings.banned_words.contains(&word) {
+        return send_embed_message(ctx, msg, &catalog, NOT_BANNED_MSG).await;
+    }
+    settings.banned_words.retain(|w| *w != word);
+    wordle_map.settings.save();
+    send_embed_message(ctx, msg, &catalog, WORD_UNBANNED_MSG).await
+}
+
+/* Lists all games played on the server with their players and remaining time. */
+#[command]
+async fn games(ctx: &Context, msg: &Message) -> CommandResult {
+    let mut wordle_data = ctx.data.write().await;
+    let mut wordle_map = wordle_data
+        .get_mut::<ServerKey>()
+        .expect("Failed to retrieve wordle map!")
+        .lock()
+        .await;
+    let catalog = wordle_map.user_catalog(msg.author.id, msg.guild_id);
+
+    end_timed_out_games(&ctx.http, &mut wordle_map);
+
+    let group = wordle_map.max_people_playing > 1;
+    let mut entries = Vec::new();
+    for (&(channel, player), (wordle, time)) in wordle_map.games.iter() {
+        if wordle.guild_id != msg.guild_id {
+            continue;
+        }
+        let seconds = wordle.time_left(*time).to_string();
+        let players = if group {
+            wordle_map.joined_people.clone()
+        } else {
+            vec![player]
+        };
+        let players = players
+            .iter()
+            .map(|p| format!("<@{}>", p.0))
+            .collect::<Vec<String>>()
+            .join(", ");
+        let gathering = group && wordle_map.joined_people.len() < wordle_map.max_people_playing;
+        entries.push(if gathering {
+            catalog.format(
+                LOBBY_ENTRY_MSG,
+                &[
+                    ("channel", channel.0.to_string()),
+                    ("players", players),
+                    ("joined", wordle_map.joined_people.len().to_string()),
+                    ("size", wordle_map.max_people_playing.to_string()),
+                    ("seconds", seconds),
+                ],
+            )
+        } else {
+            catalog.format(
+                GAME_ENTRY_MSG,
+                &[
+                    ("channel", channel.0.to_string()),
+                    ("players", players),
+                    ("guesses", wordle.guesses.to_string()),
+                    ("limit", wordle.rules.guesses.to_string()),
+                    ("seconds", seconds),
+                ],
+            )
+        });
+    }
+
+    if entries.is_empty() {
+        return send_embed_message(ctx, msg, &catalog, NO_GAMES_MSG).await;
+    }
+    let list = catalog.format(GAMES_LIST_MSG, &[("games", entries.join("\n"))]);
+    send_embed_text(ctx, msg, &catalog, &list).await
+}
+
+/* Returns the prefix chosen for the server or the one from the config file. */
+#[hook]
+async fn guild_prefix(ctx: &Context, msg: &Message) -> Option<String> {
+    let wordle_data = ctx.data.read().await;
+    let wordle_map = wordle_data
+        .get::<ServerKey>()
+        .expect("Failed to retrieve wordle map!")
+        .lock()
+        .await;
+    let prefix = wordle_map.settings.guild(msg.guild_id).prefix.clone();
+    Some(prefix.unwrap_or_else(|| wordle_map.prefix.clone()))
+}
+
+/* Commands that post boards or start games, which are limited for every user and channel. */
+const LIMITED_COMMANDS: [&str; 6] = ["start", "join", "guess", "hint", "pause", "resume"];
+
+/* Ignores commands sent on channels that are not allowed on the server
+ * and commands of users who went over the rate limits, telling them once when they can continue.
+ * Settings can be changed on any channel, so that administrators can't lock themselves out. */
+#[hook]
+async fn allowed_channel(ctx: &Context, msg: &Message, command_name: &str) -> bool {
+    Span::current().record("name", command_name);
+    debug!("Received a command");
+    if command_name == "config" {
+        return true;
+    }
+    let wordle_data = ctx.data.read().await;
+    let mut wordle_map = wordle_data
+        .get::<ServerKey>()
+        .expect("Failed to retrieve wordle map!")
+        .lock()
+        .await;
+    let channel = parent_channel(&wordle_map, msg.channel_id);
+    if !wordle_map
+        .settings
+        .guild(msg.guild_id)
+        .allows_channel(channel)
+    {
+        return false;
+    }
+    if !LIMITED_COMMANDS.contains(&command_name) {
+        return true;
+    }
+    match wordle_map.limiter.check(msg.author.id, msg.channel_id) {
+        Verdict::Allowed => true,
+        Verdict::Cooldown(seconds, first) => {
+            debug!(seconds, "Command rate limited");
+            if first {
+                let catalog = wordle_map.user_catalog(msg.author.id, msg.guild_id);
+                drop(wordle_map);
+                let text = catalog.format(COOLDOWN_MSG, &[("seconds", seconds.to_string())]);
+                let _ = send_embed_text(ctx, msg, &catalog, &text).await;
+            }
+            false
+        }
+    }
+}
+
+/* Records how long commands took and logs errors they returned, which would otherwise be lost. */
+#[hook]
+async fn after_command(_: &Context, _: &Message, command_name: &str, result: CommandResult) {
+    METRICS.observe_command(command_name);
+    if let Err(why) = result {
+        error!(command = command_name, error = %why, "Command failed");
+    }
+}
+
+/* Declaration of a set of available commands. */
+#[group("public")]
+#[commands(
+    start,
+    guess,
+    hint,
+    pause,
+    resume,
+    history,
+    replay,
+    leaderboard,
+    profile,
+    rating,
+    help,
+    join,
+    giveup,
+    challenge,
+    home,
+    locale,
+    serverlocale,
+    guild_config
+)]
+struct Public;
+
+/* Commands for moderators of a server. */
+#[group("moderation")]
+#[only_in(guilds)]
+#[checks(Moderator)]
+#[commands(endgame, resetlobby, banword, unbanword, games)]
+struct Moderation;
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let config = match Config::load() {
+        Ok(config) => config,
+        Err(why) => {
+            logging::init(&Config::default());
+            error!(error = %why, "Failed to load config");
+            return ExitCode::FAILURE;
+        }
+    };
+    logging::init(&config);
+    let server = match ServerMap::new(&config).await {
+        Ok(server) => Arc::new(Mutex::new(server)),
+        Err(why) => {
+            error!(error = %why, "Failed to load saved data");
+            return ExitCode::FAILURE;
+        }
+    };
+    let client = ClientBuilder::new(
+        config.token(),
+        /* Servers are needed in the cache to check permissions of moderators. */
+        GatewayIntents::GUILDS
+            .union(GatewayIntents::GUILD_MESSAGES)
+            .union(GatewayIntents::MESSAGE_CONTENT)
+            .union(GatewayIntents::GUILD_MESSAGE_REACTIONS)
+            .union(GatewayIntents::DIRECT_MESSAGES)
+            .union(GatewayIntents::DIRECT_MESSAGE_REACTIONS),
+    )
+    .event_handler(Handler)
+    .framework(TracedFramework(
+        StandardFramework::new()
+            /* Prefixes are chosen per server, with the one from the config file as the default. */
+            .configure(|c| {
+                c.with_whitespace(true)
+                    .prefixes(Vec::<String>::new())
+                    .dynamic_prefix(guild_prefix)
+            })
+            .before(allowed_channel)
+            .after(after_command)
+            .group(&PUBLIC_GROUP)
+            .group(&MODERATION_GROUP),
+    ))
+    .type_map_insert::<ServerKey>(server.clone())
+    .await;
+    let mut client = match client {
+        Ok(client) => client,
+        Err(why) => {
+            error!(error = %why, "Couldn't create the new client");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    tokio::spawn(run_timers(
+        client.cache_and_http.http.clone(),
+        server.clone(),
+    ));
+
+    if let Some(addr) = config.http_addr() {
+        let state = HttpState {
+            server,
+            shard_manager: client.shard_manager.clone(),
+            discord: client.cache_and_http.http.clone(),
+            admin_token: config.admin_token().map(String::from),
+            words_url: config.words_url().to_string(),
+            data_dir: config.data_dir().to_string(),
+        };
+        tokio::spawn(http::serve(addr, Arc::new(state)));
+    }
 
     if let Err(why) = client.start().await {
-        println!("Client error: {}", why)
+        error!(error = %why, "Client error");
+        return ExitCode::FAILURE;
     }
+    ExitCode::SUCCESS
 }
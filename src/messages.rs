@@ -1,47 +1,143 @@
-use crate::wordle::{DEFAULT_SIZE, GUESSES};
+use crate::achievements::{Achievement, STREAK_DAYS};
+use crate::challenge::Challenge;
+use crate::game::Game;
+use crate::history::{GameRecord, Outcome};
+use crate::locales::Catalog;
+use crate::metrics::METRICS;
+use crate::ratings::Rating;
+use crate::scores::{Season, Standing};
+use crate::solver::GuessAnalysis;
+use crate::wordle::{Rules, DEFAULT_SIZE};
+use crate::words::Words;
 use crate::Wordle;
-use const_format::formatcp;
 use serde_json::{json, Value};
 use serenity::framework::standard::CommandResult;
+use serenity::futures::TryFutureExt;
 use serenity::http::Http;
 use serenity::model::prelude::{ChannelId, Message, ReactionType, UserId};
 use serenity::prelude::{Context, SerenityError};
 use serenity::utils::Colour;
 
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use string_builder::Builder;
+use tracing::{error, warn};
 
-/* Messages send by bot. */
-pub const HELLO_MSG: &str = "Hello, I'm a Wordle Bot";
-pub const HELP_MSG: &str = formatcp!("Type `!start` to start the game.\n**Rules:**\nYou have {} tries to guess a {}-letter word in 5 minutes.\n\
-    To guess type `!guess [Your guess]`.\nAfter each guess the color of the letters will change to show how close your guess was to the word.\n\
-    If the letter is **green**, it is in the word and in the correct spot.\nIf the letter is **yellow**, it is in the word but in the wrong spot.\n\
-    If the letter is **red**, it is not in the word in any spot.\n\n\
-    If you want to give up, type `!giveup` or click on the white flag emoji under the latest display of your Wordle.\n
-    Type `!start <number_of_players>` to start a game with friends.\n\
-    **Additional rules for groups:**\nYou have 5 minutes to gather a specified number of players.\nTo join a group type `!join`.\n\
-    A group can only play if there are no solo games and if there are no other groups playing.", GUESSES, DEFAULT_SIZE);
-pub const GROUP_PLAYING_MSG: &str = "A group is playing, wait for the game to finish!";
-pub const GAME_STARTED_MSG: &str = "Game started! Take a guess using `!guess [Your guess]`.";
-pub const WRONG_PLAYERS_NUMBER_MSG: &str = "If you want to play alone type `!start`! \
-     If you want to play in a group, you need at least two players!";
-pub const SOLO_PLAYING_MSG: &str = "Someone is playing, wait for the game(s) to finish!";
-pub const WAIT_FOR_PLAYERS_MSG: &str = "Wait for other players to start the game! \
-     To join a game type `!join`.";
-pub const ALREADY_JOINED_MSG: &str = "You already joined a group!";
-pub const WRONG_CHANNEL_MSG: &str = "If you want to join your friends type `!join` \
-     on a channel where the game was initiated!";
-pub const START_GROUP_MSG: &str = "To start playing with friends type `!start <number_of_players>`";
-pub const GUESS_WRONG_CHANNEL_MSG: &str = "Type your guess on a channel where the game started!";
-pub const NOT_IN_GROUP_MSG: &str = "You can't giveup! You are not in a game!";
-pub const INCORRECT_GUESS_MSG: &str = "Guess word must contain 5 letters without numbers";
-pub const NOT_IN_LIST_MSG: &str = "Guess word is not in word list";
-pub const START_PLAYING_MSG: &str = "If you want to play alone type `!start`! \
-     To start playing with friends, type `!start <number_of_player>`!";
-pub const WON_MSG: &str = "you won! 🎉";
-pub const TOO_MANY_GUESSES_MSG: &str = "You ran out of guesses!";
-pub const YOUR_GUESSES_MSG: &str = " your guesses: \n";
-pub const GUESS_AGAIN: &str = "Guess again!";
-const DICTIONARY_REQUEST: &str = "https://api.dictionaryapi.dev/api/v2/entries/en/";
+/* Names of messages sent by bot. Their texts in every language are in 'locales/<code>.ron'. */
+pub const HELLO_MSG: &str = "hello";
+pub const HELP_MSG: &str = "help";
+pub const GROUP_PLAYING_MSG: &str = "group_playing";
+pub const GAME_STARTED_MSG: &str = "game_started";
+pub const WRONG_PLAYERS_NUMBER_MSG: &str = "wrong_players_number";
+pub const SOLO_PLAYING_MSG: &str = "solo_playing";
+pub const WAIT_FOR_PLAYERS_MSG: &str = "wait_for_players";
+pub const ALREADY_JOINED_MSG: &str = "already_joined";
+pub const WRONG_CHANNEL_MSG: &str = "wrong_channel";
+pub const START_GROUP_MSG: &str = "start_group";
+pub const GUESS_WRONG_CHANNEL_MSG: &str = "guess_wrong_channel";
+pub const NOT_IN_GROUP_MSG: &str = "not_in_group";
+pub const INCORRECT_GUESS_MSG: &str = "incorrect_guess";
+pub const NOT_IN_LIST_MSG: &str = "not_in_list";
+pub const START_PLAYING_MSG: &str = "start_playing";
+pub const WON_MSG: &str = "won";
+pub const TOO_MANY_GUESSES_MSG: &str = "too_many_guesses";
+pub const YOUR_GUESSES_MSG: &str = "your_guesses";
+pub const GUESS_AGAIN: &str = "guess_again";
+pub const YOUR_WORD_WAS_MSG: &str = "your_word_was";
+pub const JOINED_GROUP_MSG: &str = "joined_group";
+pub const WAIT_FOR_GROUP_MSG: &str = "wait_for_group";
+pub const START_OPTIONS_MSG: &str = "start_options";
+pub const PACK_AND_LANG_MSG: &str = "pack_and_lang";
+pub const UNKNOWN_PACK_MSG: &str = "unknown_pack";
+pub const UNKNOWN_LANGUAGE_MSG: &str = "unknown_language";
+pub const CHALLENGE_USAGE_MSG: &str = "challenge_usage";
+pub const CHALLENGE_WORD_MSG: &str = "challenge_word";
+pub const CHALLENGE_CHANNEL_MSG: &str = "challenge_channel";
+pub const CHALLENGE_SET_MSG: &str = "challenge_set";
+pub const CHALLENGE_TAKEN_MSG: &str = "challenge_taken";
+pub const NO_CHALLENGE_MSG: &str = "no_challenge";
+pub const CHALLENGE_PLAYED_MSG: &str = "challenge_played";
+pub const CHALLENGE_ANNOUNCEMENT_MSG: &str = "challenge_announcement";
+pub const CHALLENGE_REPORT_MSG: &str = "challenge_report";
+pub const LOCALE_USAGE_MSG: &str = "locale_usage";
+pub const LOCALE_SET_MSG: &str = "locale_set";
+pub const SERVER_LOCALE_USAGE_MSG: &str = "server_locale_usage";
+pub const SERVER_LOCALE_SET_MSG: &str = "server_locale_set";
+pub const HARD_MODE_MSG: &str = "hard_mode";
+pub const CONFIG_MSG: &str = "config";
+pub const CONFIG_SET_MSG: &str = "config_set";
+pub const INVALID_SETTING_MSG: &str = "invalid_setting";
+pub const UNKNOWN_SETTING_MSG: &str = "unknown_setting";
+pub const ENDGAME_USAGE_MSG: &str = "endgame_usage";
+pub const NO_GAMES_FOUND_MSG: &str = "no_games_found";
+pub const GAMES_ENDED_MSG: &str = "games_ended";
+pub const GAME_ENDED_BY_MODERATOR_MSG: &str = "game_ended_by_moderator";
+pub const NO_LOBBY_MSG: &str = "no_lobby";
+pub const LOBBY_RESET_MSG: &str = "lobby_reset";
+pub const BANWORD_USAGE_MSG: &str = "banword_usage";
+pub const WORD_BANNED_MSG: &str = "word_banned";
+pub const WORD_UNBANNED_MSG: &str = "word_unbanned";
+pub const NOT_BANNED_MSG: &str = "not_banned";
+pub const NO_GAMES_MSG: &str = "no_games";
+pub const GAMES_LIST_MSG: &str = "games_list";
+pub const GAME_ENTRY_MSG: &str = "game_entry";
+pub const LOBBY_ENTRY_MSG: &str = "lobby_entry";
+pub const HINT_MSG: &str = "hint";
+pub const NO_CANDIDATES_MSG: &str = "no_candidates";
+pub const ANALYSIS_MSG: &str = "analysis";
+pub const ANALYSIS_ROW_MSG: &str = "analysis_row";
+pub const SHARE_MSG: &str = "share";
+pub const HISTORY_MSG: &str = "history";
+pub const HISTORY_ENTRY_MSG: &str = "history_entry";
+pub const NO_HISTORY_MSG: &str = "no_history";
+pub const REPLAY_MSG: &str = "replay";
+pub const REPLAY_USAGE_MSG: &str = "replay_usage";
+pub const NO_GAME_RECORD_MSG: &str = "no_game_record";
+pub const OUTCOME_WON_MSG: &str = "outcome_won";
+pub const OUTCOME_LOST_MSG: &str = "outcome_lost";
+pub const OUTCOME_GAVE_UP_MSG: &str = "outcome_gave_up";
+pub const OUTCOME_ENDED_MSG: &str = "outcome_ended";
+pub const OUTCOME_TIMED_OUT_MSG: &str = "outcome_timed_out";
+pub const COOLDOWN_MSG: &str = "cooldown";
+pub const GROUP_IN_DM_MSG: &str = "group_in_dm";
+pub const HOME_SET_MSG: &str = "home_set";
+pub const HOME_CLEARED_MSG: &str = "home_cleared";
+pub const HOME_USAGE_MSG: &str = "home_usage";
+pub const THREAD_NAME_MSG: &str = "thread_name";
+pub const GAME_IN_THREAD_MSG: &str = "game_in_thread";
+pub const ALREADY_PLAYING_MSG: &str = "already_playing";
+pub const PAUSED_MSG: &str = "paused";
+pub const ALREADY_PAUSED_MSG: &str = "already_paused";
+pub const NO_PAUSE_LEFT_MSG: &str = "no_pause_left";
+pub const RESUMED_MSG: &str = "resumed";
+pub const NOT_PAUSED_MSG: &str = "not_paused";
+pub const BOARD_TIME_LEFT_MSG: &str = "board_time_left";
+pub const BOARD_PAUSED_MSG: &str = "board_paused";
+pub const TIME_WARNING_MSG: &str = "time_warning";
+pub const TIME_UP_MSG: &str = "time_up";
+pub const LOBBY_TIME_LEFT_MSG: &str = "lobby_time_left";
+pub const LOBBY_CLOSED_MSG: &str = "lobby_closed";
+pub const POINTS_EARNED_MSG: &str = "points_earned";
+pub const SEASON_LEADERBOARD_MSG: &str = "season_leaderboard";
+pub const ALL_TIME_LEADERBOARD_MSG: &str = "all_time_leaderboard";
+pub const LEADERBOARD_ENTRY_MSG: &str = "leaderboard_entry";
+pub const NO_SCORES_MSG: &str = "no_scores";
+pub const LEADERBOARD_USAGE_MSG: &str = "leaderboard_usage";
+pub const ACHIEVEMENT_SOLVED_IN_TWO_MSG: &str = "achievement_solved_in_two";
+pub const ACHIEVEMENT_STREAK_MSG: &str = "achievement_streak";
+pub const ACHIEVEMENT_HARD_MODE_MSG: &str = "achievement_hard_mode";
+pub const ACHIEVEMENT_NEVER_USED_E_MSG: &str = "achievement_never_used_e";
+pub const ACHIEVEMENT_GROUP_MVP_MSG: &str = "achievement_group_mvp";
+pub const ACHIEVEMENT_UNLOCKED_MSG: &str = "achievement_unlocked";
+pub const PROFILE_MSG: &str = "profile";
+pub const NO_ACHIEVEMENTS_MSG: &str = "no_achievements";
+pub const RATING_MSG: &str = "rating";
+pub const NO_RATING_MSG: &str = "no_rating";
+pub const RATING_LEADERBOARD_MSG: &str = "rating_leaderboard";
+pub const RATING_ENTRY_MSG: &str = "rating_entry";
+pub const NO_RATINGS_MSG: &str = "no_ratings";
+
+/* Seconds to wait for a definition before showing the word without it. */
+const DICTIONARY_TIMEOUT: u64 = 5;
 
 /* Sends the contents of message_builder to a channel. */
 pub async fn send_builder_contents(
@@ -77,13 +173,14 @@ pub async fn send_message(
 
 /* Adds a white flag reaction under a message.
  * The message is supposed to display the current state of the game. */
-pub async fn react_to_message(http: &Http, message: &Message, wordle: &mut Wordle) {
-    wordle.last_message_id = Some(message.id);
+pub async fn react_to_message(http: &Http, message: &Message, wordle: &mut Game) {
+    wordle.board_message_id = Some(message.id);
     if let Err(why) = message
         .react(http, ReactionType::Unicode(String::from("🏳")))
         .await
     {
-        println!("Could not react to the message; {}", why);
+        warn!(error = %why, "Could not react to the message");
+        METRICS.api_errors.inc();
     }
 }
 
@@ -94,28 +191,253 @@ fn list_players(builder: &mut Builder, players: Vec<UserId>) {
     }
 }
 
-/* Displays current state of a wordle. */
-pub fn display_wordle(wordle: &Wordle, players: Vec<UserId>) -> String {
+fn outcome_message(outcome: Outcome) -> &'static str {
+    match outcome {
+        Outcome::Won => OUTCOME_WON_MSG,
+        Outcome::Lost => OUTCOME_LOST_MSG,
+        Outcome::GaveUp => OUTCOME_GAVE_UP_MSG,
+        Outcome::Ended => OUTCOME_ENDED_MSG,
+        Outcome::TimedOut => OUTCOME_TIMED_OUT_MSG,
+    }
+}
+
+fn achievement_message(achievement: Achievement) -> &'static str {
+    match achievement {
+        Achievement::SolvedInTwo => ACHIEVEMENT_SOLVED_IN_TWO_MSG,
+        Achievement::Streak => ACHIEVEMENT_STREAK_MSG,
+        Achievement::HardMode => ACHIEVEMENT_HARD_MODE_MSG,
+        Achievement::NeverUsedE => ACHIEVEMENT_NEVER_USED_E_MSG,
+        Achievement::GroupMvp => ACHIEVEMENT_GROUP_MVP_MSG,
+    }
+}
+
+fn describe_achievement(achievement: Achievement, catalog: &Catalog) -> String {
+    catalog.format(
+        achievement_message(achievement),
+        &[("days", STREAK_DAYS.to_string())],
+    )
+}
+
+/* Announces achievements unlocked by players of a game, one line per achievement. */
+pub fn unlocked_achievements(unlocked: &[(UserId, Achievement)], catalog: &Catalog) -> String {
+    let lines: Vec<String> = unlocked
+        .iter()
+        .map(|&(player, achievement)| {
+            let values = [
+                ("player", player.0.to_string()),
+                ("achievement", describe_achievement(achievement, catalog)),
+            ];
+            catalog.format(ACHIEVEMENT_UNLOCKED_MSG, &values)
+        })
+        .collect();
+    lines.join("\n")
+}
+
+/* Lists achievements on a player's profile. */
+pub fn achievement_list(achievements: &[Achievement], catalog: &Catalog) -> String {
+    if achievements.is_empty() {
+        return catalog.get(NO_ACHIEVEMENTS_MSG).to_string();
+    }
+    let lines: Vec<String> = achievements
+        .iter()
+        .map(|&achievement| format!("🏅 {}", describe_achievement(achievement, catalog)))
+        .collect();
+    lines.join("\n")
+}
+
+/* Placeholders shared by the list of games and the replay of a game. */
+fn game_record_values(game: &GameRecord, catalog: &Catalog) -> Vec<(&'static str, String)> {
+    let players: Vec<String> = game.players.iter().map(|p| format!("<@{}>", p)).collect();
+    vec![
+        ("id", game.id.to_string()),
+        ("players", players.join(", ")),
+        (
+            "outcome",
+            catalog.get(outcome_message(game.outcome)).to_string(),
+        ),
+        ("guesses", game.guesses.len().to_string()),
+        ("limit", game.max_guesses.to_string()),
+        ("hints", game.hints.to_string()),
+        ("word", game.word.clone()),
+        ("started", game.started.to_string()),
+        ("finished", game.finished.to_string()),
+    ]
+}
+
+/* Lists finished games, one line per game. */
+pub fn history_list(games: &[&GameRecord], catalog: &Catalog) -> String {
+    let entries: Vec<String> = games
+        .iter()
+        .map(|game| catalog.format(HISTORY_ENTRY_MSG, &game_record_values(game, catalog)))
+        .collect();
+    catalog.format(HISTORY_MSG, &[("games", entries.join("\n"))])
+}
+
+/* Lists players of a leaderboard with their points, in a season or of all time. */
+pub fn leaderboard_list(
+    standings: &[Standing],
+    season: Option<Season>,
+    catalog: &Catalog,
+) -> String {
+    let entries: Vec<String> = standings
+        .iter()
+        .enumerate()
+        .map(|(place, standing)| {
+            let values = [
+                ("place", (place + 1).to_string()),
+                ("player", standing.player.0.to_string()),
+                ("points", standing.points.to_string()),
+                ("wins", standing.wins.to_string()),
+            ];
+            catalog.format(LEADERBOARD_ENTRY_MSG, &values)
+        })
+        .collect();
+    let entries = entries.join("\n");
+    match season {
+        Some(season) => catalog.format(
+            SEASON_LEADERBOARD_MSG,
+            &[("season", season.to_string()), ("players", entries)],
+        ),
+        None => catalog.format(ALL_TIME_LEADERBOARD_MSG, &[("players", entries)]),
+    }
+}
+
+/* Lists the highest rated players of a server with their ratings. */
+pub fn rating_list(ranking: &[(UserId, Rating)], catalog: &Catalog) -> String {
+    let entries: Vec<String> = ranking
+        .iter()
+        .enumerate()
+        .map(|(place, (player, rating))| {
+            let values = [
+                ("place", (place + 1).to_string()),
+                ("player", player.0.to_string()),
+                ("rating", format!("{:.0}", rating.rating)),
+                ("games", rating.games.to_string()),
+            ];
+            catalog.format(RATING_ENTRY_MSG, &values)
+        })
+        .collect();
+    catalog.format(RATING_LEADERBOARD_MSG, &[("players", entries.join("\n"))])
+}
+
+/* Displays the board of a finished game together with its details. */
+pub fn display_replay(game: &GameRecord, catalog: &Catalog) -> String {
+    let mut builder = Builder::default();
+    builder.append(catalog.format(REPLAY_MSG, &game_record_values(game, catalog)));
+    game.replay().display_game(&mut builder);
+    builder.string().unwrap_or_default()
+}
+
+/* Returns the unix time in given number of seconds, used in Discord's timestamps
+ * which every player sees counting down in their own timezone. */
+pub fn deadline(seconds: u64) -> u64 {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+    now + seconds
+}
+
+/* Displays current state of a wordle. Games that are still played, i.e. have some time left,
+ * show when their time runs out or that they are paused. */
+pub fn display_wordle(
+    wordle: &Wordle,
+    players: Vec<UserId>,
+    time_left: Option<u64>,
+    catalog: &Catalog,
+) -> String {
     let mut builder = Builder::default();
     list_players(&mut builder, players);
-    builder.append(YOUR_GUESSES_MSG);
+    builder.append(catalog.get(YOUR_GUESSES_MSG));
     wordle.display_game(&mut builder);
-    builder.append(GUESS_AGAIN);
+    if let Some(seconds) = time_left {
+        if wordle.is_paused() && wordle.pause_left() > 0 {
+            builder.append(catalog.get(BOARD_PAUSED_MSG));
+        } else {
+            let deadline = deadline(seconds).to_string();
+            builder.append(catalog.format(BOARD_TIME_LEFT_MSG, &[("deadline", deadline)]));
+        }
+        builder.append(catalog.get(GUESS_AGAIN));
+    }
     builder.string().unwrap()
 }
 
-/* Fetches a definition for a given word from a dictionary API.
+/* Shows the current state of a game on its board message, editing it if the game has one,
+ * so that the channel isn't flooded with boards and the white flag stays under the same message.
+ * A new board is sent if the game has none yet or the old one can't be edited, e.g. it was deleted. */
+pub async fn update_board(
+    http: &Http,
+    channel: &ChannelId,
+    wordle: &mut Game,
+    players: Vec<UserId>,
+    time_left: Option<u64>,
+    catalog: &Catalog,
+) {
+    let board = display_wordle(wordle, players, time_left, catalog);
+    if let Some(id) = wordle.board_message_id {
+        match channel.edit_message(http, id, |m| m.content(&board)).await {
+            Ok(_) => return,
+            Err(why) => warn!(%channel, error = %why, "Couldn't edit the board, sending a new one"),
+        }
+    }
+    match send_string(http, channel, &board).await {
+        Ok(message) => react_to_message(http, &message, wordle).await,
+        Err(why) => {
+            error!(%channel, error = %why, "Error sending the message");
+            METRICS.api_errors.inc();
+        }
+    }
+}
+
+/* Removes a guess once it's shown on the board, so that the channel stays readable.
+ * Where the bot can't delete messages, e.g. in direct messages, it reacts to the guess instead. */
+pub async fn acknowledge_guess(http: &Http, msg: &Message) {
+    if msg.guild_id.is_some() && msg.delete(http).await.is_ok() {
+        return;
+    }
+    if let Err(why) = msg
+        .react(http, ReactionType::Unicode(String::from("✅")))
+        .await
+    {
+        warn!(error = %why, "Could not react to the guess");
+        METRICS.api_errors.inc();
+    }
+}
+
+/* Creates the rules of the game based on its current settings. */
+pub fn help_message(catalog: &Catalog, words: &Words, rules: Rules) -> String {
+    let mut languages = vec!["en"];
+    languages.extend(words.language_codes());
+    catalog.format(
+        HELP_MSG,
+        &[
+            ("guesses", rules.guesses.to_string()),
+            ("size", DEFAULT_SIZE.to_string()),
+            ("minutes", rules.time_limit.div_ceil(60).to_string()),
+            ("packs", words.pack_names().join(", ")),
+            ("languages", languages.join(", ")),
+        ],
+    )
+}
+
+/* Fetches a definition for a given word from a dictionary API available at the given url.
  * Returns the first definition found.
- * If there has been an error, returns an empty String. */
-async fn get_definition(word: &str) -> String {
+ * If there has been an error, including a response with an error status, returns an empty String. */
+async fn get_definition(dictionary_url: &str, word: &str) -> String {
     let mut definition = String::from("");
     let default = json!("");
-    let mut url = String::from(DICTIONARY_REQUEST);
+    let mut url = String::from(dictionary_url);
     url.push_str(word);
-    let request = reqwest::get(url).await;
+    let request = reqwest::Client::new()
+        .get(url)
+        .timeout(Duration::from_secs(DICTIONARY_TIMEOUT))
+        .send()
+        .await
+        .and_then(|response| response.error_for_status());
     match request {
         Err(why) => {
-            println!("Error fetching the definition: {}", why)
+            METRICS.dictionary_errors.inc();
+            warn!(word, error = %why, "Error fetching the definition")
         }
         Ok(response) => {
             if let Err(why) = response
@@ -131,7 +453,8 @@ async fn get_definition(word: &str) -> String {
                     definition = value.to_string();
                 })
             {
-                println!("Error reading the definition: {}", why);
+                METRICS.dictionary_errors.inc();
+                warn!(word, error = %why, "Error reading the definition");
             }
         }
     }
@@ -145,12 +468,19 @@ pub async fn send_wordle_solution(
     channel: &ChannelId,
     players: Vec<UserId>,
     http: &Http,
+    catalog: &Catalog,
+    dictionary_url: &str,
 ) {
     let mut builder = Builder::default();
     list_players(&mut builder, players);
-    builder.append("your word was:");
+    builder.append(catalog.get(YOUR_WORD_WAS_MSG));
     if let Ok(string) = builder.string() {
-        let definition = get_definition(wordle.word.as_str()).await;
+        /* The dictionary only has definitions of English words. */
+        let definition = if wordle.lang.is_none() {
+            get_definition(dictionary_url, wordle.word.as_str()).await
+        } else {
+            String::new()
+        };
 
         if let Err(why) = channel
             .send_message(http, |m| {
@@ -162,21 +492,153 @@ pub async fn send_wordle_solution(
             })
             .await
         {
-            println!("Error sending the message: {}", why);
+            error!(%channel, error = %why, "Error sending the message");
+            METRICS.api_errors.inc();
         }
-    } else {
     }
 }
 
-pub async fn send_embed_message(ctx: &Context, msg: &Message, message: &str) -> CommandResult {
-    if let Err(why) = msg
-        .channel_id
+/* Sends the spoiler-free result of a finished game to its channel
+ * and posts it with the players' names to the results channel of the server if there is one. */
+pub async fn send_share_grid(
+    http: &Http,
+    channel: &ChannelId,
+    results_channel: Option<ChannelId>,
+    wordle: &Wordle,
+    solved: bool,
+    players: Vec<UserId>,
+    catalog: &Catalog,
+) {
+    let grid = wordle.share_grid(solved);
+    let share = format!("{}\n```\n{}\n```", catalog.get(SHARE_MSG), grid);
+    if let Err(why) = send_string(http, channel, &share).await {
+        error!(%channel, error = %why, "Error sending the message");
+        METRICS.api_errors.inc();
+    }
+    if let Some(results) = results_channel {
+        if let Err(why) = send_message(&format!("\n{}", grid), Some(players), http, &results).await
+        {
+            error!(channel = %results, error = %why, "Error sending the results");
+            METRICS.api_errors.inc();
+        }
+    }
+}
+
+/* Sends the analysis of a finished game to its channel, one line per guess. */
+pub async fn send_analysis(
+    http: &Http,
+    channel: &ChannelId,
+    analysis: &[GuessAnalysis],
+    catalog: &Catalog,
+) {
+    let rows: Vec<String> = analysis
+        .iter()
+        .map(|row| {
+            catalog.format(
+                ANALYSIS_ROW_MSG,
+                &[
+                    ("guess", row.guess.iter().collect()),
+                    ("before", row.before.to_string()),
+                    ("after", row.after.to_string()),
+                    ("skill", row.skill.to_string()),
+                    ("luck", row.luck.to_string()),
+                ],
+            )
+        })
+        .collect();
+    if let Err(why) = channel
+        .send_message(http, |m| {
+            m.embed(|e| {
+                e.title(catalog.get(ANALYSIS_MSG))
+                    .description(rows.join("\n"))
+                    .color(Colour::new(0xff6905))
+            })
+        })
+        .await
+    {
+        error!(%channel, error = %why, "Error sending the analysis");
+        METRICS.api_errors.inc();
+    }
+}
+
+/* Replies with the message of the given name. */
+pub async fn send_embed_message(
+    ctx: &Context,
+    msg: &Message,
+    catalog: &Catalog,
+    message: &str,
+) -> CommandResult {
+    send_embed_text(ctx, msg, catalog, catalog.get(message)).await
+}
+
+/* Replies with the given text, e.g. a message with filled placeholders. */
+pub async fn send_embed_text(
+    ctx: &Context,
+    msg: &Message,
+    catalog: &Catalog,
+    text: &str,
+) -> CommandResult {
+    send_embed_to(ctx, msg.channel_id, catalog, text).await
+}
+
+/* Sends the given text to a channel, e.g. to the thread of a game. */
+pub async fn send_embed_to(
+    ctx: &Context,
+    channel: ChannelId,
+    catalog: &Catalog,
+    text: &str,
+) -> CommandResult {
+    if let Err(why) = channel
         .send_message(ctx, |m| {
-            m.embed(|e| e.title(HELLO_MSG).description(message))
+            m.embed(|e| e.title(catalog.get(HELLO_MSG)).description(text))
         })
         .await
     {
-        println!("Error sending the help message: {}", why);
+        error!(error = %why, "Error sending the reply");
+        METRICS.api_errors.inc();
     }
     Ok(())
 }
+
+/* Announces a new challenge on the channel it was set on. */
+pub async fn send_challenge_announcement(
+    http: &Http,
+    channel: &ChannelId,
+    setter: UserId,
+    catalog: &Catalog,
+) {
+    let announcement = catalog.format(
+        CHALLENGE_ANNOUNCEMENT_MSG,
+        &[("setter", setter.0.to_string())],
+    );
+    if let Err(why) = channel
+        .send_message(http, |m| {
+            m.embed(|e| e.title(catalog.get(HELLO_MSG)).description(announcement))
+        })
+        .await
+    {
+        error!(%channel, error = %why, "Error sending the message");
+        METRICS.api_errors.inc();
+    }
+}
+
+/* Lets the setter of a challenge know how many people have solved it so far. */
+pub async fn send_challenge_report(http: &Http, challenge: &Challenge, catalog: &Catalog) {
+    let report = catalog.format(
+        CHALLENGE_REPORT_MSG,
+        &[
+            ("solvers", challenge.solvers.len().to_string()),
+            ("players", challenge.players.len().to_string()),
+            ("word", challenge.word.clone()),
+        ],
+    );
+    if let Err(why) = challenge
+        .setter
+        .create_dm_channel(http)
+        .and_then(|channel| async move { channel.say(http, report).await })
+        .await
+    {
+        error!(setter = challenge.setter.0, error = %why, "Error sending the message");
+        METRICS.api_errors.inc();
+    }
+}
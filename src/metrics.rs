@@ -0,0 +1,133 @@
+use crate::ServerMap;
+use once_cell::sync::Lazy;
+use prometheus::core::Collector;
+use prometheus::{
+    Encoder, HistogramOpts, HistogramVec, IntCounter, IntGauge, IntGaugeVec, Opts, Registry,
+    TextEncoder,
+};
+use std::time::Instant;
+use tracing::error;
+
+tokio::task_local! {
+    /* Time the framework started handling the message that is being handled. */
+    pub static DISPATCHED: Instant;
+}
+
+pub static METRICS: Lazy<Metrics> = Lazy::new(Metrics::new);
+
+/* Metrics exposed to operators in the Prometheus text format.
+ * Gauges describing games and words are updated when metrics are collected. */
+pub struct Metrics {
+    registry: Registry,
+    games: IntGaugeVec,
+    lobbies: IntGauge,
+    pub guesses: IntCounter,
+    commands: HistogramVec,
+    pub api_errors: IntCounter,
+    pub dictionary_errors: IntCounter,
+    words: IntGaugeVec,
+}
+
+fn register<T: Collector + Clone + 'static>(registry: &Registry, metric: T) -> T {
+    registry
+        .register(Box::new(metric.clone()))
+        .expect("Failed to register a metric");
+    metric
+}
+
+impl Metrics {
+    fn new() -> Metrics {
+        let registry = Registry::new_custom(Some(String::from("wordle")), None)
+            .expect("Failed to create the registry of metrics");
+        Metrics {
+            games: register(
+                &registry,
+                IntGaugeVec::new(Opts::new("active_games", "Games being played"), &["mode"])
+                    .unwrap(),
+            ),
+            lobbies: register(
+                &registry,
+                IntGauge::new("lobbies", "Groups gathering players").unwrap(),
+            ),
+            guesses: register(
+                &registry,
+                IntCounter::new("guesses_total", "Guesses made in all games").unwrap(),
+            ),
+            commands: register(
+                &registry,
+                HistogramVec::new(
+                    HistogramOpts::new("command_duration_seconds", "Time of handling commands"),
+                    &["command"],
+                )
+                .unwrap(),
+            ),
+            api_errors: register(
+                &registry,
+                IntCounter::new("discord_api_errors_total", "Failed requests to Discord").unwrap(),
+            ),
+            dictionary_errors: register(
+                &registry,
+                IntCounter::new(
+                    "dictionary_errors_total",
+                    "Failed requests to the dictionary API, including error statuses",
+                )
+                .unwrap(),
+            ),
+            words: register(
+                &registry,
+                IntGaugeVec::new(
+                    Opts::new("words", "Words on the lists of words"),
+                    &["kind", "name"],
+                )
+                .unwrap(),
+            ),
+            registry,
+        }
+    }
+
+    /* Records the time of handling a command, measured from the moment its message was dispatched. */
+    pub fn observe_command(&self, command: &str) {
+        if let Ok(elapsed) = DISPATCHED.try_with(|started| started.elapsed()) {
+            self.commands
+                .with_label_values(&[command])
+                .observe(elapsed.as_secs_f64());
+        }
+    }
+
+    /* Sets gauges describing the current games and lists of words. */
+    pub fn update(&self, map: &ServerMap) {
+        let games = map.games.len() as i64;
+        let group = map.max_people_playing > 1;
+        let gathering = group && map.joined_people.len() < map.max_people_playing;
+        self.games
+            .with_label_values(&["solo"])
+            .set(if group { 0 } else { games });
+        self.games
+            .with_label_values(&["group"])
+            .set(if group && !gathering { games } else { 0 });
+        self.lobbies.set(if gathering { games } else { 0 });
+
+        self.words
+            .with_label_values(&["main", "en"])
+            .set(map.words.words.len() as i64);
+        for (name, pack) in &map.words.packs {
+            self.words
+                .with_label_values(&["pack", name])
+                .set(pack.words.len() as i64);
+        }
+        for (code, language) in &map.words.languages {
+            self.words
+                .with_label_values(&["language", code])
+                .set(language.words.len() as i64);
+        }
+    }
+
+    /* Returns all metrics in the Prometheus text format. */
+    pub fn encode(&self) -> String {
+        let mut buffer = Vec::new();
+        if let Err(why) = TextEncoder::new().encode(&self.registry.gather(), &mut buffer) {
+            error!(error = %why, "Error encoding metrics");
+        }
+        String::from_utf8(buffer).unwrap_or_default()
+    }
+}
@@ -0,0 +1,52 @@
+use crate::messages::{PACK_AND_LANG_MSG, START_OPTIONS_MSG, WRONG_PLAYERS_NUMBER_MSG};
+use serenity::framework::standard::Args;
+
+/* Settings of a new game given as arguments of the start command,
+ * e.g. `!start 3 pack=programming` or `!start lang=pl`. */
+pub struct StartOptions {
+    pub number_of_players: usize,
+    /* Whether to play the challenge set on the channel. */
+    pub challenge: bool,
+    pub pack: Option<String>,
+    /* Code of the language of the word, none for English. */
+    pub lang: Option<String>,
+}
+
+impl StartOptions {
+    /* Parses arguments of the start command in any order.
+     * Returns a message explaining the mistake if any of them is incorrect. */
+    pub fn parse(mut args: Args) -> Result<StartOptions, &'static str> {
+        let mut options = StartOptions {
+            number_of_players: 1,
+            challenge: false,
+            pack: None,
+            lang: None,
+        };
+
+        while !args.is_empty() {
+            let arg = args
+                .single_quoted::<String>()
+                .map_err(|_| START_OPTIONS_MSG)?;
+            if arg == "challenge" {
+                options.challenge = true;
+            } else if let Some(pack) = arg.strip_prefix("pack=") {
+                options.pack = Some(pack.to_lowercase());
+            } else if let Some(lang) = arg.strip_prefix("lang=") {
+                let lang = lang.to_lowercase();
+                options.lang = if lang == "en" { None } else { Some(lang) };
+            } else if let Ok(number) = arg.parse::<usize>() {
+                if number <= 1 {
+                    return Err(WRONG_PLAYERS_NUMBER_MSG);
+                }
+                options.number_of_players = number;
+            } else {
+                return Err(START_OPTIONS_MSG);
+            }
+        }
+        /* Word packs are made of English words. */
+        if options.pack.is_some() && options.lang.is_some() {
+            return Err(PACK_AND_LANG_MSG);
+        }
+        Ok(options)
+    }
+}
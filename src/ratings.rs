@@ -0,0 +1,137 @@
+use crate::storage;
+use serde::{Deserialize, Serialize};
+use serenity::model::id::{GuildId, UserId};
+use std::collections::HashMap;
+
+const RATINGS_FILE: &str = "ratings.ron";
+/* Rating of players who haven't played a rated game yet. */
+const INITIAL_RATING: f64 = 1500.0;
+/* Largest change of a rating after a single game. */
+const K_FACTOR: f64 = 32.0;
+
+/* Elo rating of a player on a server. */
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub struct Rating {
+    pub rating: f64,
+    /* Number of rated games the player has played. */
+    pub games: u32,
+}
+
+impl Default for Rating {
+    fn default() -> Rating {
+        Rating {
+            rating: INITIAL_RATING,
+            games: 0,
+        }
+    }
+}
+
+/* Chance of a player with the first rating beating a player with the second one. */
+fn expected_score(rating: f64, opponent: f64) -> f64 {
+    1.0 / (1.0 + 10f64.powf((opponent - rating) / 400.0))
+}
+
+/* Ratings of players on every server, saved in the storage directory after every rated game. */
+pub struct Ratings {
+    guilds: HashMap<u64, HashMap<u64, Rating>>,
+    storage_dir: String,
+}
+
+impl Ratings {
+    pub fn load(storage_dir: &str) -> Result<Ratings, String> {
+        Ok(Ratings {
+            guilds: storage::load(storage_dir, RATINGS_FILE)?,
+            storage_dir: storage_dir.to_string(),
+        })
+    }
+
+    /* Updates ratings of both players of a head-to-head game and returns how many points
+     * the winner gained, which is also how many the loser lost. */
+    pub fn record(&mut self, guild: GuildId, winner: UserId, loser: UserId) -> f64 {
+        let players = self.guilds.entry(guild.0).or_default();
+        let winner_rating = players.get(&winner.0).copied().unwrap_or_default();
+        let loser_rating = players.get(&loser.0).copied().unwrap_or_default();
+        let change = K_FACTOR * (1.0 - expected_score(winner_rating.rating, loser_rating.rating));
+        players.insert(
+            winner.0,
+            Rating {
+                rating: winner_rating.rating + change,
+                games: winner_rating.games + 1,
+            },
+        );
+        players.insert(
+            loser.0,
+            Rating {
+                rating: loser_rating.rating - change,
+                games: loser_rating.games + 1,
+            },
+        );
+        storage::save(&self.storage_dir, RATINGS_FILE, &self.guilds);
+        change
+    }
+
+    /* Returns players of a server who have played rated games, the highest rated first. */
+    pub fn ranking(&self, guild: GuildId) -> Vec<(UserId, Rating)> {
+        let mut ranking: Vec<(UserId, Rating)> = self
+            .guilds
+            .get(&guild.0)
+            .map(|players| {
+                players
+                    .iter()
+                    .map(|(&player, &rating)| (UserId(player), rating))
+                    .collect()
+            })
+            .unwrap_or_default();
+        ranking.sort_by(|(a_player, a), (b_player, b)| {
+            b.rating
+                .total_cmp(&a.rating)
+                .then(a_player.0.cmp(&b_player.0))
+        });
+        ranking
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GUILD: GuildId = GuildId(1);
+
+    /* Creates an empty storage directory of a test in the temporary directory. */
+    fn storage_dir(test: &str) -> String {
+        let dir = std::env::temp_dir().join(format!("wordle-{}-{}", test, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir.to_str().unwrap().to_string()
+    }
+
+    #[test]
+    fn expected_score_of_equal_and_distant_ratings() {
+        assert_eq!(expected_score(1500.0, 1500.0), 0.5);
+        assert!((expected_score(1900.0, 1500.0) - 10.0 / 11.0).abs() < 1e-9);
+        assert!((expected_score(1500.0, 1900.0) - 1.0 / 11.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn winners_gain_what_losers_lose() {
+        let dir = storage_dir("ratings");
+        let mut ratings = Ratings::load(&dir).unwrap();
+        assert_eq!(ratings.record(GUILD, UserId(1), UserId(2)), K_FACTOR / 2.0);
+        /* Beating a higher rated player is worth more than beating a lower rated one. */
+        let upset = ratings.record(GUILD, UserId(2), UserId(1));
+        assert!(upset > K_FACTOR / 2.0);
+        let change = ratings.record(GUILD, UserId(2), UserId(3));
+        assert!(change < K_FACTOR / 2.0);
+
+        let ratings = Ratings::load(&dir).unwrap();
+        let ranking = ratings.ranking(GUILD);
+        let players: Vec<u64> = ranking.iter().map(|(player, _)| player.0).collect();
+        assert_eq!(players, vec![2, 1, 3]);
+        assert_eq!(ranking[0].1.games, 3);
+        assert_eq!(ranking[1].1.games, 2);
+        let total: f64 = ranking.iter().map(|(_, rating)| rating.rating).sum();
+        assert!((total - 3.0 * INITIAL_RATING).abs() < 1e-9);
+        assert!(ratings.ranking(GuildId(2)).is_empty());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
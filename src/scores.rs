@@ -0,0 +1,364 @@
+use crate::storage;
+use crate::wordle::Wordle;
+use serde::{Deserialize, Serialize};
+use serenity::model::id::{GuildId, UserId};
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const SCORES_FILE: &str = "scores.ron";
+/* Number of players listed by `!leaderboard`. */
+pub const LEADERBOARD_SIZE: usize = 10;
+
+/* Points of a won game before bonuses and multipliers. */
+const BASE_POINTS: f64 = 50.0;
+/* Points for every guess left unused. */
+const GUESS_POINTS: f64 = 20.0;
+/* Points for solving instantly, given in proportion to the time left. */
+const TIME_POINTS: f64 = 50.0;
+const HARD_MODE_MULTIPLIER: f64 = 1.5;
+
+/* Everything about a won game that its points depend on. */
+pub struct Play {
+    pub guesses: u32,
+    pub max_guesses: u32,
+    pub time_left: u64,
+    pub time_limit: u64,
+    pub hard_mode: bool,
+    pub hints: u32,
+    /* Difficulty of the word, from 0 for the easiest words to 1. */
+    pub difficulty: f64,
+    pub players: usize,
+}
+
+impl Play {
+    pub fn new(wordle: &Wordle, time_left: u64, difficulty: f64, players: usize) -> Play {
+        Play {
+            guesses: wordle.guesses,
+            max_guesses: wordle.rules.guesses,
+            time_left,
+            time_limit: wordle.rules.time_limit,
+            hard_mode: wordle.rules.hard_mode,
+            hints: wordle.hints,
+            difficulty,
+            players,
+        }
+    }
+
+    /* Points every player of the game gets. Fewer guesses and a faster solve give more points,
+     * so do hard mode and rare letters, while hints and help of a bigger group give fewer. */
+    pub fn points(&self) -> u32 {
+        let unused = self.max_guesses.saturating_sub(self.guesses) as f64;
+        let speed = self.time_left as f64 / self.time_limit.max(1) as f64;
+        let mut points = BASE_POINTS + unused * GUESS_POINTS + speed.min(1.0) * TIME_POINTS;
+        if self.hard_mode {
+            points *= HARD_MODE_MULTIPLIER;
+        }
+        points *= 1.0 + self.difficulty.clamp(0.0, 1.0) / 2.0;
+        points /= (1 + self.hints) as f64;
+        points *= 2.0 / (self.players.max(1) + 1) as f64;
+        points.round() as u32
+    }
+}
+
+/* A calendar month, in which players compete for the most points. */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Season {
+    pub year: i64,
+    pub month: u32,
+}
+
+impl Season {
+    /* Returns the season of a time given in seconds since the Unix epoch, in UTC. */
+    pub fn of(time: u64) -> Season {
+        /* Converts days since the epoch to a date, as described in
+         * http://howardhinnant.github.io/date_algorithms.html#civil_from_days */
+        let days = (time / 86400) as i64 + 719468;
+        let era = days.div_euclid(146097);
+        let day_of_era = days.rem_euclid(146097);
+        let year_of_era =
+            (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let month_index = (5 * day_of_year + 2) / 153;
+        let month = (if month_index < 10 {
+            month_index + 3
+        } else {
+            month_index - 9
+        }) as u32;
+        let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+        Season { year, month }
+    }
+
+    pub fn current() -> Season {
+        Season::of(unix_time(SystemTime::now()))
+    }
+}
+
+impl fmt::Display for Season {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}-{:02}", self.year, self.month)
+    }
+}
+
+/* Parses seasons written as `<year>-<month>`, e.g. `2024-03`. */
+impl FromStr for Season {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Season, ()> {
+        let (year, month) = s.split_once('-').ok_or(())?;
+        let year = year.parse().map_err(|_| ())?;
+        let month = month.parse().map_err(|_| ())?;
+        if !(1..=12).contains(&month) {
+            return Err(());
+        }
+        Ok(Season { year, month })
+    }
+}
+
+/* Points a player got for a single game. */
+#[derive(Serialize, Deserialize)]
+pub struct Score {
+    pub player: u64,
+    /* Id of the game in the history. */
+    pub game: u64,
+    /* Server the points count on. Games in direct messages count on the player's home server. */
+    pub guild: Option<u64>,
+    pub points: u32,
+    /* Time the game was won at, in seconds since the Unix epoch. */
+    pub earned: u64,
+}
+
+impl Score {
+    /* Whether the points count on a server, in a season or, if none is given, of all time. */
+    fn counts(&self, guild: Option<GuildId>, season: Option<Season>) -> bool {
+        self.guild == guild.map(|g| g.0) && season.is_none_or(|s| Season::of(self.earned) == s)
+    }
+}
+
+/* A player's place on a leaderboard. */
+pub struct Standing {
+    pub player: UserId,
+    pub points: u32,
+    pub wins: usize,
+}
+
+fn unix_time(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0)
+}
+
+/* Points of all players. Each score is appended to the file in the storage directory when it's earned. */
+pub struct Scores {
+    players: HashMap<u64, Vec<Score>>,
+    storage_dir: String,
+}
+
+impl Scores {
+    pub fn load(storage_dir: &str) -> Scores {
+        let mut players: HashMap<u64, Vec<Score>> = HashMap::new();
+        for score in storage::load_records::<Score>(storage_dir, SCORES_FILE) {
+            players.entry(score.player).or_default().push(score);
+        }
+        Scores {
+            players,
+            storage_dir: storage_dir.to_string(),
+        }
+    }
+
+    /* Gives points for a won game to a player. */
+    pub fn add(&mut self, player: UserId, game: u64, guild: Option<GuildId>, points: u32) {
+        let score = Score {
+            player: player.0,
+            game,
+            guild: guild.map(|g| g.0),
+            points,
+            earned: unix_time(SystemTime::now()),
+        };
+        storage::append_record(&self.storage_dir, SCORES_FILE, &score);
+        self.players.entry(player.0).or_default().push(score);
+    }
+
+    /* Returns the points of a player on a server, in a season or of all time. */
+    pub fn total(&self, player: UserId, guild: Option<GuildId>, season: Option<Season>) -> u32 {
+        self.players
+            .get(&player.0)
+            .map(|scores| {
+                scores
+                    .iter()
+                    .filter(|score| score.counts(guild, season))
+                    .map(|score| score.points)
+                    .sum()
+            })
+            .unwrap_or(0)
+    }
+
+    /* Returns the players with the most points on a server, in a season or of all time. */
+    pub fn leaderboard(
+        &self,
+        guild: Option<GuildId>,
+        season: Option<Season>,
+        count: usize,
+    ) -> Vec<Standing> {
+        let mut standings: Vec<Standing> = self
+            .players
+            .iter()
+            .map(|(&player, scores)| {
+                let scores: Vec<&Score> = scores
+                    .iter()
+                    .filter(|score| score.counts(guild, season))
+                    .collect();
+                Standing {
+                    player: UserId(player),
+                    points: scores.iter().map(|score| score.points).sum(),
+                    wins: scores.len(),
+                }
+            })
+            .filter(|standing| standing.wins > 0)
+            .collect();
+        standings.sort_by(|a, b| b.points.cmp(&a.points).then(a.player.0.cmp(&b.player.0)));
+        standings.truncate(count);
+        standings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: u64 = 86400;
+
+    #[test]
+    fn season_of_the_epoch() {
+        assert_eq!(
+            Season::of(0),
+            Season {
+                year: 1970,
+                month: 1
+            }
+        );
+        assert_eq!(
+            Season::of(DAY - 1),
+            Season {
+                year: 1970,
+                month: 1
+            }
+        );
+    }
+
+    #[test]
+    fn season_of_a_leap_day() {
+        let leap_day = 951782400;
+        assert_eq!(
+            Season::of(leap_day),
+            Season {
+                year: 2000,
+                month: 2
+            }
+        );
+        assert_eq!(
+            Season::of(leap_day + DAY - 1),
+            Season {
+                year: 2000,
+                month: 2
+            }
+        );
+        assert_eq!(
+            Season::of(leap_day + DAY),
+            Season {
+                year: 2000,
+                month: 3
+            }
+        );
+    }
+
+    #[test]
+    fn season_at_the_turn_of_a_year() {
+        let new_year = 1735689600;
+        assert_eq!(
+            Season::of(new_year - DAY),
+            Season {
+                year: 2024,
+                month: 12
+            }
+        );
+        assert_eq!(
+            Season::of(new_year - 1),
+            Season {
+                year: 2024,
+                month: 12
+            }
+        );
+        assert_eq!(
+            Season::of(new_year),
+            Season {
+                year: 2025,
+                month: 1
+            }
+        );
+        assert_eq!(Season::of(new_year).to_string(), "2025-01");
+    }
+
+    /* A win in 3 of 6 guesses with no time left, worth 50 + 3 * 20 points before multipliers. */
+    fn play() -> Play {
+        Play {
+            guesses: 3,
+            max_guesses: 6,
+            time_left: 0,
+            time_limit: 300,
+            hard_mode: false,
+            hints: 0,
+            difficulty: 0.0,
+            players: 1,
+        }
+    }
+
+    #[test]
+    fn points_of_a_plain_win() {
+        assert_eq!(play().points(), 110);
+    }
+
+    #[test]
+    fn hard_mode_multiplies_points() {
+        let play = Play {
+            hard_mode: true,
+            ..play()
+        };
+        assert_eq!(play.points(), 165);
+    }
+
+    #[test]
+    fn hints_divide_points() {
+        assert_eq!(Play { hints: 1, ..play() }.points(), 55);
+        assert_eq!(Play { hints: 3, ..play() }.points(), 28);
+    }
+
+    #[test]
+    fn bigger_groups_get_fewer_points() {
+        assert_eq!(
+            Play {
+                players: 0,
+                ..play()
+            }
+            .points(),
+            110
+        );
+        assert_eq!(
+            Play {
+                players: 2,
+                ..play()
+            }
+            .points(),
+            73
+        );
+        assert_eq!(
+            Play {
+                players: 3,
+                ..play()
+            }
+            .points(),
+            55
+        );
+    }
+}
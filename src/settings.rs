@@ -0,0 +1,119 @@
+use crate::storage;
+use crate::wordle::Rules;
+use serde::{Deserialize, Serialize};
+use serenity::model::id::{ChannelId, GuildId, UserId};
+use std::collections::HashMap;
+
+const SETTINGS_FILE: &str = "guilds.ron";
+const HOMES_FILE: &str = "homes.ron";
+
+/* Server and channel results of a player's games in direct messages are announced on,
+ * unless the server has its own channel for results. */
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub struct Home {
+    pub guild: u64,
+    pub channel: u64,
+}
+
+/* Settings chosen by administrators of a single server.
+ * Settings that are not set fall back to the ones from the config file. */
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GuildSettings {
+    pub guesses: Option<u32>,
+    /* Time to play a game or gather a group, in seconds. */
+    pub time_limit: Option<u64>,
+    pub hard_mode: bool,
+    /* Whether guesses of every finished game are analysed. */
+    pub analysis: bool,
+    /* Word pack used if no pack or language is given when starting a game. */
+    pub pack: Option<String>,
+    /* Language of words used if no pack or language is given when starting a game. */
+    pub lang: Option<String>,
+    /* Default language of bot's messages. */
+    pub locale: Option<String>,
+    /* Channels the bot can be used on, all of them if empty. */
+    pub channels: Vec<u64>,
+    /* Channel spoiler-free results of finished games are posted to. */
+    pub results_channel: Option<u64>,
+    pub prefix: Option<String>,
+    /* Role whose members can use moderator commands besides people who can manage messages. */
+    pub moderator_role: Option<u64>,
+    /* Words that are never picked as answers on the server. */
+    pub banned_words: Vec<String>,
+}
+
+impl GuildSettings {
+    /* Rules of new games started on the server. */
+    pub fn rules(&self, default: Rules) -> Rules {
+        Rules {
+            guesses: self.guesses.unwrap_or(default.guesses),
+            time_limit: self.time_limit.unwrap_or(default.time_limit),
+            hard_mode: self.hard_mode,
+            max_pause: default.max_pause,
+        }
+    }
+
+    pub fn allows_channel(&self, channel: ChannelId) -> bool {
+        self.channels.is_empty() || self.channels.contains(&channel.0)
+    }
+}
+
+/* Settings of all servers and home servers of players, saved in the storage directory after every change. */
+pub struct Settings {
+    guilds: HashMap<u64, GuildSettings>,
+    homes: HashMap<u64, Home>,
+    default: GuildSettings,
+    storage_dir: String,
+}
+
+impl Settings {
+    pub fn load(storage_dir: &str) -> Result<Settings, String> {
+        Ok(Settings {
+            guilds: storage::load(storage_dir, SETTINGS_FILE)?,
+            homes: storage::load(storage_dir, HOMES_FILE)?,
+            default: GuildSettings::default(),
+            storage_dir: storage_dir.to_string(),
+        })
+    }
+
+    pub fn save(&self) {
+        storage::save(&self.storage_dir, SETTINGS_FILE, &self.guilds);
+    }
+
+    /* Returns settings of a server or the default ones, e.g. in direct messages. */
+    pub fn guild(&self, guild: Option<GuildId>) -> &GuildSettings {
+        guild
+            .and_then(|g| self.guilds.get(&g.0))
+            .unwrap_or(&self.default)
+    }
+
+    pub fn guild_mut(&mut self, guild: GuildId) -> &mut GuildSettings {
+        self.guilds.entry(guild.0).or_default()
+    }
+
+    pub fn home(&self, player: UserId) -> Option<Home> {
+        self.homes.get(&player.0).copied()
+    }
+
+    /* Chooses or clears the home server of a player and saves homes of all players. */
+    pub fn set_home(&mut self, player: UserId, home: Option<Home>) {
+        match home {
+            Some(home) => self.homes.insert(player.0, home),
+            None => self.homes.remove(&player.0),
+        };
+        storage::save(&self.storage_dir, HOMES_FILE, &self.homes);
+    }
+
+    /* Returns the channel results of a game are posted to, if one is chosen.
+     * Results of games in direct messages go to the home server of the player. */
+    pub fn results_channel(&self, guild: Option<GuildId>, player: UserId) -> Option<ChannelId> {
+        if guild.is_some() {
+            return self.guild(guild).results_channel.map(ChannelId);
+        }
+        self.home(player).map(|home| {
+            let guild = self.guild(Some(GuildId(home.guild)));
+            ChannelId(guild.results_channel.unwrap_or(home.channel))
+        })
+    }
+}
@@ -0,0 +1,225 @@
+use crate::wordle::{compare, square, Field, DEFAULT_SIZE};
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+
+/* Number of different results a guess can get. */
+const PATTERNS: usize = 3_usize.pow(DEFAULT_SIZE as u32);
+
+/* Turns the result of comparing a guess with a word into a number, so that results can be counted. */
+fn pattern(word: &[char], guess: &[char]) -> usize {
+    guess.iter().enumerate().fold(0, |code, (pos, &letter)| {
+        code * 3 + square(word, letter, pos) as usize
+    })
+}
+
+/* Checks whether a word would give the same results as the ones shown in the fields of a game. */
+pub fn matches(word: &[char], fields: &HashMap<u32, Vec<Field>>) -> bool {
+    fields.values().all(|row| {
+        let guess: Vec<char> = row.iter().map(|field| field.letter).collect();
+        compare(word, &guess)
+            .into_iter()
+            .zip(row)
+            .all(|(square, field)| square == field.square)
+    })
+}
+
+/* Returns the words that can still be the answer of a game with the given fields. */
+pub fn candidates(answers: &[&str], fields: &HashMap<u32, Vec<Field>>) -> Vec<Vec<char>> {
+    answers
+        .iter()
+        .map(|word| word.chars().collect::<Vec<char>>())
+        .filter(|word| matches(word, fields))
+        .collect()
+}
+
+/* Counts how many candidates would give each result of a guess. */
+fn pattern_counts(guess: &[char], candidates: &[Vec<char>]) -> [u32; PATTERNS] {
+    let mut counts = [0_u32; PATTERNS];
+    for candidate in candidates {
+        counts[pattern(candidate, guess)] += 1;
+    }
+    counts
+}
+
+/* Expected information, in bits, a guess gives about which of the candidates is the answer. */
+pub fn entropy(guess: &[char], candidates: &[Vec<char>]) -> f64 {
+    let counts = pattern_counts(guess, candidates);
+    let total = candidates.len() as f64;
+    counts
+        .iter()
+        .filter(|&&count| count > 0)
+        .map(|&count| {
+            let p = count as f64 / total;
+            p * (1.0 / p).log2()
+        })
+        .sum()
+}
+
+/* Picks the guess that gives the most information about the candidates.
+ * If guesses are equally good, a candidate is preferred, as it can also be the answer.
+ * If there are no known guesses, only the candidates are tried. Returns none if there are no candidates. */
+pub fn best_guess(guesses: &[Vec<char>], candidates: &[Vec<char>]) -> Option<(Vec<char>, f64)> {
+    /* With one or two candidates, guessing one of them is always the best choice. */
+    if candidates.len() <= 2 {
+        return candidates
+            .first()
+            .map(|word| (word.clone(), entropy(word, candidates)));
+    }
+
+    let guesses = if guesses.is_empty() {
+        candidates
+    } else {
+        guesses
+    };
+    let candidate_set: HashSet<&Vec<char>> = candidates.iter().collect();
+    let mut best: Option<(&Vec<char>, f64, bool)> = None;
+    for guess in guesses {
+        let score = entropy(guess, candidates);
+        let candidate = candidate_set.contains(guess);
+        let better = match best {
+            None => true,
+            Some((_, best_score, best_candidate)) => {
+                score > best_score + f64::EPSILON
+                    || ((score - best_score).abs() <= f64::EPSILON && candidate && !best_candidate)
+            }
+        };
+        if better {
+            best = Some((guess, score, candidate));
+        }
+    }
+    best.map(|(guess, score, _)| (guess.clone(), score))
+}
+
+/* How much a single guess of a finished game narrowed down the answer. */
+pub struct GuessAnalysis {
+    pub guess: Vec<char>,
+    /* Number of words that could be the answer before and after the guess. */
+    pub before: usize,
+    pub after: usize,
+    /* Information the guess gave compared with the best guess, in percent. */
+    pub skill: u32,
+    /* Chance that the guess would have left more words, in percent. */
+    pub luck: u32,
+}
+
+/* Analyses every guess of a game with the given answer, like WordleBot does. */
+pub fn analyse(
+    answer: &[char],
+    rows: &[Vec<char>],
+    answers: &[Vec<char>],
+    guesses: &[Vec<char>],
+) -> Vec<GuessAnalysis> {
+    let mut candidates = answers.to_vec();
+    rows.iter()
+        .map(|guess| {
+            let before = candidates.len();
+            let best = best_guess(guesses, &candidates).map_or(0.0, |(_, score)| score);
+            let skill = if best > 0.0 {
+                (entropy(guess, &candidates) / best * 100.0)
+                    .round()
+                    .min(100.0) as u32
+            } else {
+                100
+            };
+
+            let counts = pattern_counts(guess, &candidates);
+            let result = pattern(answer, guess);
+            candidates.retain(|candidate| pattern(candidate, guess) == result);
+            let after = candidates.len();
+            /* Results leaving more words count fully, the ones leaving as many count by half. */
+            let unluckier: f64 = counts
+                .iter()
+                .map(|&count| match (count as usize).cmp(&after) {
+                    Ordering::Greater => count as f64,
+                    Ordering::Equal => count as f64 / 2.0,
+                    Ordering::Less => 0.0,
+                })
+                .sum();
+            let luck = if before > 0 {
+                (unluckier / before as f64 * 100.0).round() as u32
+            } else {
+                50
+            };
+
+            GuessAnalysis {
+                guess: guess.clone(),
+                before,
+                after,
+                skill,
+                luck,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wordle::{Result, Rules, Wordle};
+
+    fn chars(word: &str) -> Vec<char> {
+        word.chars().collect()
+    }
+
+    /* Reads the result of every letter back from a pattern. */
+    fn decode(mut code: usize) -> Vec<Result> {
+        let mut squares = vec![Result::Red; DEFAULT_SIZE];
+        for square in squares.iter_mut().rev() {
+            *square = match code % 3 {
+                0 => Result::Green,
+                1 => Result::Yellow,
+                _ => Result::Red,
+            };
+            code /= 3;
+        }
+        squares
+    }
+
+    #[test]
+    fn pattern_matches_compare() {
+        let pairs = [
+            ("CRANE", "CRANE"),
+            ("CRANE", "NACRE"),
+            ("SLATE", "CRANE"),
+            ("LLAMA", "ALLEY"),
+            ("ŻABKA", "KABZA"),
+            ("MOUSE", "PIZZA"),
+        ];
+        for (word, guess) in pairs {
+            let (word, guess) = (chars(word), chars(guess));
+            assert!(decode(pattern(&word, &guess)) == compare(&word, &guess));
+        }
+    }
+
+    #[test]
+    fn candidates_follow_the_board() {
+        let rules = Rules {
+            guesses: 6,
+            time_limit: 0,
+            hard_mode: false,
+            max_pause: 0,
+        };
+        let mut wordle = Wordle::new(String::from("SLATE"), rules);
+        wordle.guesses = 1;
+        wordle.add_fields(String::from("CRANE"));
+
+        let answers = ["SLATE", "PLATE", "CRANE", "BRAKE", "STALE", "SHINE"];
+        let expected: Vec<Vec<char>> = ["SLATE", "PLATE", "STALE"].map(chars).to_vec();
+        assert_eq!(candidates(&answers, &wordle.fields), expected);
+        assert_eq!(candidates(&answers, &HashMap::new()).len(), answers.len());
+    }
+
+    #[test]
+    fn best_guess_prefers_candidates_on_ties() {
+        let candidates = ["ABCDE", "ABCDF", "ABCDG"].map(chars).to_vec();
+        /* Both guesses leave one candidate or the other two. */
+        let guesses = ["EXXXX", "ABCDE"].map(chars).to_vec();
+        let (guess, _) = best_guess(&guesses, &candidates).unwrap();
+        assert_eq!(guess, chars("ABCDE"));
+
+        /* A guess telling all candidates apart is still better. */
+        let guesses = ["ABCDE", "EFXXX"].map(chars).to_vec();
+        let (guess, _) = best_guess(&guesses, &candidates).unwrap();
+        assert_eq!(guess, chars("EFXXX"));
+    }
+}
@@ -0,0 +1,113 @@
+use ron::ser::{to_string_pretty, PrettyConfig};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::error;
+
+/* Reads data saved in a file in the storage directory.
+ * Returns the default value if the file doesn't exist yet or can't be read.
+ * A file that can't be parsed, e.g. after a typo made editing it, is moved aside
+ * as `<file>.corrupt-<time>` first, so that the next save doesn't overwrite it.
+ * Returns an error if it can't be moved, as the next save would overwrite it. */
+pub fn load<T: DeserializeOwned + Default>(
+    storage_dir: &str,
+    file_name: &str,
+) -> Result<T, String> {
+    let path = Path::new(storage_dir).join(file_name);
+    match std::fs::File::open(&path) {
+        Err(why) => {
+            if why.kind() != std::io::ErrorKind::NotFound {
+                error!(path = %path.display(), error = %why, "Error reading saved data");
+            }
+            Ok(T::default())
+        }
+        Ok(f) => ron::de::from_reader(f).or_else(|why| {
+            let time = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|duration| duration.as_secs())
+                .unwrap_or(0);
+            let corrupt_path =
+                Path::new(storage_dir).join(format!("{}.corrupt-{}", file_name, time));
+            std::fs::rename(&path, &corrupt_path).map_err(|rename_error| {
+                format!(
+                    "Error parsing {}: {}. It couldn't be moved aside either: {}",
+                    path.display(),
+                    why,
+                    rename_error
+                )
+            })?;
+            error!(
+                path = %path.display(),
+                moved_to = %corrupt_path.display(),
+                error = %why,
+                "Error parsing saved data, the file was moved aside"
+            );
+            Ok(T::default())
+        }),
+    }
+}
+
+/* Reads records appended to a file in the storage directory, one per line, in the order they were added.
+ * Lines that can't be parsed are skipped; they stay in the file, as records are never rewritten. */
+pub fn load_records<T: DeserializeOwned>(storage_dir: &str, file_name: &str) -> Vec<T> {
+    let path = Path::new(storage_dir).join(file_name);
+    let contents = match std::fs::read_to_string(&path) {
+        Ok(contents) => contents,
+        Err(why) => {
+            if why.kind() != std::io::ErrorKind::NotFound {
+                error!(path = %path.display(), error = %why, "Error reading saved data");
+            }
+            return Vec::new();
+        }
+    };
+    contents
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .filter_map(|(number, line)| {
+            ron::from_str(line)
+                .map_err(|why| {
+                    error!(path = %path.display(), line = number + 1, error = %why, "Error parsing a saved record")
+                })
+                .ok()
+        })
+        .collect()
+}
+
+/* Adds a record at the end of a file in the storage directory, creating the directory and the file if needed.
+ * Unlike saving all data, it takes the same time however many records there already are. */
+pub fn append_record<T: Serialize>(storage_dir: &str, file_name: &str, record: &T) {
+    let path = Path::new(storage_dir).join(file_name);
+    let result = ron::to_string(record)
+        .map_err(|why| why.to_string())
+        .and_then(|line| {
+            std::fs::create_dir_all(storage_dir)
+                .and_then(|_| OpenOptions::new().create(true).append(true).open(&path))
+                .and_then(|mut file| file.write_all(format!("{}\n", line).as_bytes()))
+                .map_err(|why| why.to_string())
+        });
+    if let Err(why) = result {
+        error!(path = %path.display(), error = %why, "Error saving a record");
+    }
+}
+
+/* Saves data to a file in the storage directory, creating the directory if needed.
+ * The data is written to a temporary file first, so a crash can't leave a half-written file. */
+pub fn save<T: Serialize>(storage_dir: &str, file_name: &str, data: &T) {
+    let path = Path::new(storage_dir).join(file_name);
+    let temp_path = Path::new(storage_dir).join(format!("{}.tmp", file_name));
+    let result = to_string_pretty(data, PrettyConfig::new())
+        .map_err(|why| why.to_string())
+        .and_then(|contents| {
+            std::fs::create_dir_all(storage_dir)
+                .and_then(|_| std::fs::write(&temp_path, contents))
+                .and_then(|_| std::fs::rename(&temp_path, &path))
+                .map_err(|why| why.to_string())
+        });
+    if let Err(why) = result {
+        error!(path = %path.display(), error = %why, "Error saving data");
+    }
+}
@@ -1,7 +1,9 @@
-use serenity::model::id::MessageId;
 use std::collections::HashMap;
+use std::time::SystemTime;
 use string_builder::Builder;
+use unicode_normalization::UnicodeNormalization;
 
+#[derive(Clone, Copy, PartialEq, Eq)]
 pub enum Result {
     Green,
     Yellow,
@@ -9,10 +11,58 @@ pub enum Result {
 }
 
 pub const DEFAULT_SIZE: usize = 5;
+/* Default number of guesses, can be changed in the config file. */
 pub const GUESSES: u32 = 6;
 pub const GREEN_SQUARE: &str = ":green_square: ";
 pub const YELLOW_SQUARE: &str = ":yellow_square: ";
 pub const RED_SQUARE: &str = ":red_square: ";
+/* Squares of the shared results are plain characters, so that they can be copied anywhere. */
+pub const GREEN_SHARE: char = '🟩';
+pub const YELLOW_SHARE: char = '🟨';
+pub const RED_SHARE: char = '🟥';
+
+/* Brings a word to the form it is compared in. Letters with diacritics typed as
+ * a base letter followed by a combining mark are composed into a single char, e.g. "Ż". */
+pub fn normalize(word: &str) -> String {
+    word.nfc().collect::<String>().to_uppercase()
+}
+
+/* Checks whether a normalized word has the correct number of letters and no other characters. */
+pub fn is_correct_word(word: &str) -> bool {
+    word.chars().count() == DEFAULT_SIZE && word.chars().all(char::is_alphabetic)
+}
+
+/* Compares a letter of a guess placed at the given position with the word. */
+pub fn square(word: &[char], letter: char, pos: usize) -> Result {
+    if word[pos] == letter {
+        Result::Green
+    } else if word.contains(&letter) {
+        Result::Yellow
+    } else {
+        Result::Red
+    }
+}
+
+/* Compares a guess with the word letter by letter. */
+pub fn compare(word: &[char], guess: &[char]) -> Vec<Result> {
+    guess
+        .iter()
+        .enumerate()
+        .map(|(pos, &letter)| square(word, letter, pos))
+        .collect()
+}
+
+/* Settings of a single game. */
+#[derive(Clone, Copy)]
+pub struct Rules {
+    pub guesses: u32,
+    /* Time to play a game or gather a group, in seconds. */
+    pub time_limit: u64,
+    /* In hard mode every guess must use the hints from previous guesses. */
+    pub hard_mode: bool,
+    /* Total time the game can be paused for, in seconds. */
+    pub max_pause: u64,
+}
 
 /* Struct representing a single char in guess word. */
 pub struct Field {
@@ -31,39 +81,132 @@ pub struct Wordle {
     pub word: String,
     pub guesses: u32,
     pub fields: HashMap<u32, Vec<Field>>,
-    pub last_message_id: Option<MessageId>,
+    /* Name of the word pack the word comes from. */
+    pub pack: Option<String>,
+    /* Language of the word if it is not English. */
+    pub lang: Option<String>,
+    pub rules: Rules,
+    /* Number of times players asked for a hint. */
+    pub hints: u32,
+    /* Time the game was paused at, if it's paused. */
+    pub paused_at: Option<SystemTime>,
+    /* Seconds the game was paused for before the current pause. */
+    pub paused_for: u64,
+    /* Whether the players have been told that the game is about to run out of time. */
+    pub warned: bool,
 }
 
 impl Wordle {
-    pub fn new(word: String) -> Wordle {
+    pub fn new(word: String, rules: Rules) -> Wordle {
         Wordle {
             word,
             guesses: 0,
             fields: HashMap::new(),
-            last_message_id: None,
+            pack: None,
+            lang: None,
+            rules,
+            hints: 0,
+            paused_at: None,
+            paused_for: 0,
+            warned: false,
+        }
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused_at.is_some()
+    }
+
+    pub fn pause(&mut self) {
+        self.paused_at = Some(SystemTime::now());
+    }
+
+    pub fn resume(&mut self) {
+        if let Some(time) = self.paused_at.take() {
+            self.paused_for += time.elapsed().unwrap_or_default().as_secs();
         }
     }
 
+    /* Seconds the game has been paused for, including the current pause. */
+    fn pause_time(&self) -> u64 {
+        let current = self
+            .paused_at
+            .map(|time| time.elapsed().unwrap_or_default().as_secs());
+        self.paused_for + current.unwrap_or(0)
+    }
+
+    /* Seconds the game can still be paused for. */
+    pub fn pause_left(&self) -> u64 {
+        self.rules.max_pause.saturating_sub(self.pause_time())
+    }
+
+    /* Seconds the game has been played since the given time, without pauses.
+     * Once the limit of pausing is used up, the time runs even if the game is paused.
+     * If the system clock goes back, no time counts until it catches up again. */
+    pub fn played_time(&self, started: SystemTime) -> u64 {
+        let elapsed = started.elapsed().unwrap_or_default().as_secs();
+        elapsed.saturating_sub(self.pause_time().min(self.rules.max_pause))
+    }
+
+    /* Seconds left to play the game started at the given time. */
+    pub fn time_left(&self, started: SystemTime) -> u64 {
+        self.rules
+            .time_limit
+            .saturating_sub(self.played_time(started))
+    }
+
     /* Saves guess word as Fields with corresponding color describing if char
      * matches the chars in a word to guess. */
     pub fn add_fields(&mut self, guess: String) {
-        let mut field_vec = Vec::new();
-        for (pos, c) in guess.chars().enumerate() {
-            let field: Field;
-            if c == self.word.chars().nth(pos).unwrap() {
-                field = Field::new(c, Result::Green);
-            } else if self.word.chars().any(|word_c| word_c == c) {
-                field = Field::new(c, Result::Yellow);
-            } else {
-                field = Field::new(c, Result::Red);
-            }
-            field_vec.push(field);
-        }
+        let word: Vec<char> = self.word.chars().collect();
+        let guess: Vec<char> = guess.chars().collect();
+        let field_vec = compare(&word, &guess)
+            .into_iter()
+            .zip(guess)
+            .map(|(square, letter)| Field::new(letter, square))
+            .collect();
         self.fields.insert(self.guesses, field_vec);
     }
 
+    /* Checks whether a guess uses all hints from previous guesses:
+     * green letters must stay in their spots and yellow letters must be used again. */
+    pub fn follows_hints(&self, guess: &str) -> bool {
+        let guess: Vec<char> = guess.chars().collect();
+        self.fields.values().all(|row| {
+            row.iter()
+                .enumerate()
+                .all(|(pos, field)| match field.square {
+                    Result::Green => guess[pos] == field.letter,
+                    Result::Yellow => guess.contains(&field.letter),
+                    Result::Red => true,
+                })
+        })
+    }
+
+    /* Returns the classic result of a finished game, e.g. "Wordle 4/6", with squares but without letters,
+     * so that it can be shared without spoiling the word. Games in hard mode are marked with a star. */
+    pub fn share_grid(&self, solved: bool) -> String {
+        let score = if solved {
+            self.guesses.to_string()
+        } else {
+            String::from("X")
+        };
+        let hard = if self.rules.hard_mode { "*" } else { "" };
+        let mut grid = format!("Wordle {}/{}{}", score, self.rules.guesses, hard);
+        let mut rounds: Vec<&u32> = self.fields.keys().collect();
+        rounds.sort_unstable();
+        for round in rounds {
+            grid.push('\n');
+            grid.extend(self.fields[round].iter().map(|field| match field.square {
+                Result::Green => GREEN_SHARE,
+                Result::Yellow => YELLOW_SHARE,
+                Result::Red => RED_SHARE,
+            }));
+        }
+        grid
+    }
+
     pub fn display_game(&self, string_response: &mut Builder) {
-        for round in 1..(GUESSES + 1) {
+        for round in 1..(self.rules.guesses + 1) {
             if self.guesses >= round {
                 let vec_fields = self.fields.get(&round).unwrap();
                 /* Displays guessed word. */
@@ -91,3 +234,97 @@ impl Wordle {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn squares(word: &str, guess: &str) -> String {
+        let word: Vec<char> = word.chars().collect();
+        let guess: Vec<char> = guess.chars().collect();
+        compare(&word, &guess)
+            .into_iter()
+            .map(|square| match square {
+                Result::Green => 'G',
+                Result::Yellow => 'Y',
+                Result::Red => 'R',
+            })
+            .collect()
+    }
+
+    /* Plays the given guesses in a game with the given word. */
+    fn play(word: &str, guesses: &[&str], hard_mode: bool) -> Wordle {
+        let rules = Rules {
+            guesses: GUESSES,
+            time_limit: 300,
+            hard_mode,
+            max_pause: 0,
+        };
+        let mut wordle = Wordle::new(word.to_string(), rules);
+        for guess in guesses {
+            wordle.guesses += 1;
+            wordle.add_fields(guess.to_string());
+        }
+        wordle
+    }
+
+    #[test]
+    fn normalize_composes_diacritics() {
+        assert_eq!(normalize("żabka"), "ŻABKA");
+        /* "z" followed by a combining dot above. */
+        assert_eq!(normalize("z\u{307}abka"), "ŻABKA");
+        assert_eq!(normalize("Crane"), "CRANE");
+    }
+
+    #[test]
+    fn correct_words_have_five_letters() {
+        assert!(is_correct_word("CRANE"));
+        assert!(is_correct_word("ŻABKA"));
+        assert!(is_correct_word(&normalize("z\u{307}abka")));
+        assert!(!is_correct_word("ŻÓŁW"));
+        assert!(!is_correct_word("CRANES"));
+        assert!(!is_correct_word("CR4NE"));
+        assert!(!is_correct_word(""));
+    }
+
+    #[test]
+    fn compare_marks_every_letter() {
+        assert_eq!(squares("CRANE", "CRANE"), "GGGGG");
+        assert_eq!(squares("CRANE", "NACRE"), "YYYYG");
+        assert_eq!(squares("CRANE", "MOUSY"), "RRRRR");
+        /* Every copy of a letter in the word counts, however many times it is guessed. */
+        assert_eq!(squares("ALLEY", "LLAMA"), "YGYRY");
+    }
+
+    #[test]
+    fn compare_tells_letters_with_diacritics_apart() {
+        assert_eq!(squares("ŻABKA", "KABZA"), "YGGRG");
+        assert_eq!(squares("ŻABKA", "ŻABKA"), "GGGGG");
+    }
+
+    #[test]
+    fn hard_mode_guesses_follow_hints() {
+        /* C and E are green, A is yellow. */
+        let wordle = play("CRANE", &["CABLE"], true);
+        assert!(wordle.follows_hints("CRANE"));
+        assert!(wordle.follows_hints("CHASE"));
+        assert!(!wordle.follows_hints("CHOSE"));
+        assert!(!wordle.follows_hints("ACHED"));
+        assert!(play("CRANE", &[], true).follows_hints("MOUSY"));
+    }
+
+    #[test]
+    fn share_grid_hides_letters() {
+        let wordle = play("CRANE", &["MOUSY", "CABLE", "CRANE"], false);
+        assert_eq!(
+            wordle.share_grid(true),
+            "Wordle 3/6\n🟥🟥🟥🟥🟥\n🟩🟨🟥🟥🟩\n🟩🟩🟩🟩🟩"
+        );
+    }
+
+    #[test]
+    fn share_grid_marks_lost_and_hard_games() {
+        let wordle = play("CRANE", &["CABLE"], true);
+        assert_eq!(wordle.share_grid(false), "Wordle X/6*\n🟩🟨🟥🟥🟩");
+    }
+}
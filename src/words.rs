@@ -1,5 +1,25 @@
+use crate::wordle::{is_correct_word, normalize};
 use bracket_random::prelude::RandomNumberGenerator;
+use ron::de;
 use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::Path;
+use tracing::{error, info, warn};
+
+/* Main list of words used unless another one is given in the config file. */
+pub const DEFAULT_WORDS_URL: &str =
+    "https://raw.githubusercontent.com/mongodb-developer/bash-wordle/main/words.json";
+/* The only word on the main list if it couldn't be fetched. */
+const FALLBACK_WORD: &str = "EMPTY";
+/* Large prime the day is multiplied by to pick the daily word. */
+const DAILY_STEP: u64 = 2_654_435_761;
+
+fn unique_letters(word: &str) -> Vec<char> {
+    let mut letters: Vec<char> = word.chars().collect();
+    letters.sort_unstable();
+    letters.dedup();
+    letters
+}
 
 /* Struct representing a single available word. */
 #[derive(Deserialize)]
@@ -7,37 +27,377 @@ pub struct Word {
     pub word: String,
 }
 
-/* Struct representing all available words to guess. */
+/* Describes which guesses are accepted in a game played with a word pack. */
+#[derive(Deserialize, Clone, Copy, Default)]
+pub enum GuessValidation {
+    /* A guess must be on the main list of words or in the pack. */
+    #[default]
+    WordList,
+    /* A guess must be one of the pack's words. */
+    PackOnly,
+    /* Any word of the correct length is accepted. */
+    Any,
+}
+
+/* Struct representing a named set of themed words, loaded from 'packs/<name>.ron'.
+ * Word lists of other languages, loaded from 'languages/<code>.ron', use the same format. */
+#[derive(Deserialize)]
+pub struct Pack {
+    pub words: Vec<String>,
+    #[serde(default)]
+    pub validation: GuessValidation,
+}
+
+impl Pack {
+    pub fn contains(&self, word: &str) -> bool {
+        self.words.iter().any(|w| w == word)
+    }
+
+    /* Picks a random word that is not banned. If every word is banned, any word is picked. */
+    pub fn generate_word(&self, banned: &[String]) -> &str {
+        let allowed: Vec<&String> = self.words.iter().filter(|w| !banned.contains(w)).collect();
+        let mut rng = RandomNumberGenerator::new();
+        match rng.random_slice_entry(&allowed) {
+            Some(&word) => word,
+            None => rng
+                .random_slice_entry(&self.words)
+                .expect("Error getting random word"),
+        }
+    }
+}
+
+/* Struct representing all available words to guess.
+ * The main list of words is English, the lists of other languages are keyed by their codes, e.g. "pl". */
 pub struct Words {
     pub words: Vec<Word>,
+    pub packs: HashMap<String, Pack>,
+    pub languages: HashMap<String, Pack>,
+}
+
+/* Reads every list of words from the given directory, keyed by file names.
+ * Words are normalized and the ones of incorrect length are skipped. Empty lists are not loaded. */
+fn load_word_lists(data_dir: &str, dir_name: &str) -> HashMap<String, Pack> {
+    let mut packs = HashMap::new();
+    let dir = Path::new(data_dir).join(dir_name);
+    let entries = match std::fs::read_dir(&dir) {
+        Ok(entries) => entries,
+        Err(why) => {
+            error!(dir = dir_name, error = %why, "Error reading word lists");
+            return packs;
+        }
+    };
+
+    for path in entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+    {
+        if path.extension().and_then(|ext| ext.to_str()) != Some("ron") {
+            continue;
+        }
+        let name = match path.file_stem().and_then(|stem| stem.to_str()) {
+            Some(name) => name.to_lowercase(),
+            None => continue,
+        };
+        let pack: Result<Pack, String> = std::fs::File::open(&path)
+            .map_err(|why| why.to_string())
+            .and_then(|f| de::from_reader(f).map_err(|why| why.to_string()));
+        match pack {
+            Err(why) => error!(dir = dir_name, %name, error = %why, "Error loading a word list"),
+            Ok(mut pack) => {
+                pack.words = pack
+                    .words
+                    .iter()
+                    .map(|w| normalize(w))
+                    .filter(|w| is_correct_word(w))
+                    .collect();
+                if !pack.words.is_empty() {
+                    packs.insert(name, pack);
+                }
+            }
+        }
+    }
+    packs
+}
+
+/* Gets the main list of words in json format. A url that is not http(s) is read as a local file,
+ * so that a list can be tried out before it's published. */
+async fn fetch_words(words_url: &str) -> Result<Vec<Word>, String> {
+    if !words_url.starts_with("http://") && !words_url.starts_with("https://") {
+        return std::fs::read_to_string(words_url)
+            .map_err(|why| why.to_string())
+            .and_then(|contents| serde_json::from_str(&contents).map_err(|why| why.to_string()));
+    }
+    let response = reqwest::get(words_url)
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|why| why.to_string())?;
+    response.json().await.map_err(|why| why.to_string())
 }
 
 impl Words {
-    pub async fn new() -> Words {
-        /* Get list of available words in json format. */
-        let result = reqwest::get(
-            "https://raw.githubusercontent.com/mongodb-developer/bash-wordle/main/words.json",
-        )
-        .await;
-
-        match result {
+    /* Fetches the main list of words from the given url and loads the rest from the data directory.
+     * Words of the main list are normalized and the ones that can't be played are dropped, like in packs. */
+    pub async fn new(words_url: &str, data_dir: &str) -> Words {
+        let words = match fetch_words(words_url).await {
             Err(why) => {
-                println!("Error fetching data: {}", why);
-                Words {
-                    words: vec![Word {
-                        word: String::from("EMPTY"),
-                    }],
+                error!(url = words_url, error = %why, "Error fetching the list of words");
+                Vec::new()
+            }
+            Ok(words) => {
+                let fetched = words.len();
+                let words: Vec<Word> = words
+                    .into_iter()
+                    .map(|w| Word {
+                        word: normalize(&w.word),
+                    })
+                    .filter(|w| is_correct_word(&w.word))
+                    .collect();
+                if words.len() < fetched {
+                    warn!(
+                        url = words_url,
+                        dropped = fetched - words.len(),
+                        "Dropped words that can't be played from the list of words"
+                    );
                 }
+                words
             }
-            Ok(response) => Words {
-                words: response.json().await.expect("Error parsing list of words"),
+        };
+        let words = if words.is_empty() {
+            vec![Word {
+                word: String::from(FALLBACK_WORD),
+            }]
+        } else {
+            words
+        };
+
+        let words = Words {
+            words,
+            packs: load_word_lists(data_dir, "packs"),
+            languages: load_word_lists(data_dir, "languages"),
+        };
+        info!(
+            words = words.words.len(),
+            packs = words.packs.len(),
+            languages = words.languages.len(),
+            "Loaded word lists"
+        );
+        words
+    }
+
+    /* Checks whether the main list of words couldn't be fetched and only has the fallback word. */
+    pub fn is_fallback(&self) -> bool {
+        self.words.len() == 1 && self.words[0].word == FALLBACK_WORD
+    }
+
+    /* Checks whether the given (uppercase) word is on the list of available words. */
+    pub fn contains(&self, word: &str) -> bool {
+        self.words.iter().any(|w| w.word == word)
+    }
+
+    /* Checks whether a guess is accepted in a game played with the given word pack or language
+     * or with the main list of words if there is neither. */
+    pub fn is_allowed_guess(&self, guess: &str, pack: Option<&str>, lang: Option<&str>) -> bool {
+        if let Some(language) = lang.and_then(|code| self.languages.get(code)) {
+            return match language.validation {
+                GuessValidation::WordList | GuessValidation::PackOnly => language.contains(guess),
+                GuessValidation::Any => true,
+            };
+        }
+        match pack.and_then(|name| self.packs.get(name)) {
+            None => self.contains(guess),
+            Some(pack) => match pack.validation {
+                GuessValidation::WordList => self.contains(guess) || pack.contains(guess),
+                GuessValidation::PackOnly => pack.contains(guess),
+                GuessValidation::Any => true,
             },
         }
     }
 
-    pub fn generate_word(&self) -> &Word {
+    /* Returns words that can be the answer in a game played with the given word pack or language. */
+    pub fn answers(&self, pack: Option<&str>, lang: Option<&str>) -> Vec<&str> {
+        if let Some(language) = lang.and_then(|code| self.languages.get(code)) {
+            return language.words.iter().map(|w| w.as_str()).collect();
+        }
+        match pack.and_then(|name| self.packs.get(name)) {
+            Some(pack) => pack.words.iter().map(|w| w.as_str()).collect(),
+            None => self.words.iter().map(|w| w.word.as_str()).collect(),
+        }
+    }
+
+    /* Rates how hard a word is to guess among the answers of a game played with the given word pack
+     * or language, from 0 for the easiest answer to 1 for the hardest one. Words made of letters
+     * that few answers contain are harder, and so are words with repeated letters. */
+    pub fn difficulty(&self, word: &str, pack: Option<&str>, lang: Option<&str>) -> f64 {
+        let answers = self.answers(pack, lang);
+        let mut answers_with: HashMap<char, usize> = HashMap::new();
+        for answer in &answers {
+            for letter in unique_letters(answer) {
+                *answers_with.entry(letter).or_default() += 1;
+            }
+        }
+        /* Repeated letters are only counted once, so they make a word less common. */
+        let commonness = |word: &str| -> f64 {
+            let total: usize = unique_letters(word)
+                .iter()
+                .map(|letter| answers_with.get(letter).copied().unwrap_or(0))
+                .sum();
+            total as f64 / word.chars().count().max(1) as f64
+        };
+        let word_commonness = commonness(word);
+        let more_common = answers
+            .iter()
+            .filter(|answer| commonness(answer) > word_commonness)
+            .count();
+        more_common as f64 / answers.len().max(1) as f64
+    }
+
+    /* Returns the word of the given day, counted in days since the Unix epoch, which is the same
+     * for everyone playing with the same list. Consecutive days get words from all over the list. */
+    pub fn daily_word(&self, day: u64, pack: Option<&str>, lang: Option<&str>) -> &str {
+        let answers = self.answers(pack, lang);
+        let index = day.wrapping_mul(DAILY_STEP) % answers.len().max(1) as u64;
+        answers
+            .get(index as usize)
+            .copied()
+            .unwrap_or(FALLBACK_WORD)
+    }
+
+    /* Returns all known words accepted as guesses in a game played with the given word pack or language.
+     * Lists accepting any word only give the words they contain and the main list of words. */
+    pub fn guess_list(&self, pack: Option<&str>, lang: Option<&str>) -> Vec<&str> {
+        if let Some(language) = lang.and_then(|code| self.languages.get(code)) {
+            return language.words.iter().map(|w| w.as_str()).collect();
+        }
+        let mut words: Vec<&str> = self.words.iter().map(|w| w.word.as_str()).collect();
+        if let Some(pack) = pack.and_then(|name| self.packs.get(name)) {
+            if let GuessValidation::PackOnly = pack.validation {
+                return pack.words.iter().map(|w| w.as_str()).collect();
+            }
+            words.extend(
+                pack.words
+                    .iter()
+                    .map(|w| w.as_str())
+                    .filter(|w| !self.contains(w)),
+            );
+        }
+        words
+    }
+
+    /* Returns names of all loaded word packs in alphabetical order. */
+    pub fn pack_names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self.packs.keys().map(|name| name.as_str()).collect();
+        names.sort_unstable();
+        names
+    }
+
+    /* Returns codes of all loaded languages other than English in alphabetical order. */
+    pub fn language_codes(&self) -> Vec<&str> {
+        let mut codes: Vec<&str> = self.languages.keys().map(|code| code.as_str()).collect();
+        codes.sort_unstable();
+        codes
+    }
+
+    /* Picks a random word from the main list that is not banned.
+     * If every word is banned, any word is picked. */
+    pub fn generate_word(&self, banned: &[String]) -> &Word {
+        let allowed: Vec<&Word> = self
+            .words
+            .iter()
+            .filter(|w| !banned.contains(&w.word))
+            .collect();
         let mut rng = RandomNumberGenerator::new();
-        rng.random_slice_entry(&self.words)
-            .expect("Error getting random word")
+        match rng.random_slice_entry(&allowed) {
+            Some(&word) => word,
+            None => rng
+                .random_slice_entry(&self.words)
+                .expect("Error getting random word"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /* Creates an empty data directory of a test in the temporary directory. */
+    fn data_dir(test: &str) -> String {
+        let dir = std::env::temp_dir().join(format!("wordle-{}-{}", test, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir.to_str().unwrap().to_string()
+    }
+
+    fn write(dir: &str, path: &str, contents: &str) {
+        let path = Path::new(dir).join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, contents).unwrap();
+    }
+
+    #[test]
+    fn language_lists_are_normalized() {
+        let dir = data_dir("languages");
+        write(
+            &dir,
+            "languages/pl.ron",
+            "(words: [\"żabka\", \"z\u{307}ółty\", \"kot\", \"źdźbło\"], validation: PackOnly)",
+        );
+        write(&dir, "languages/xx.ron", "(words: [\"kot\"])");
+        write(&dir, "languages/broken.ron", "(words: [");
+
+        let languages = load_word_lists(&dir, "languages");
+        assert_eq!(languages.len(), 1);
+        assert_eq!(languages["pl"].words, ["ŻABKA", "ŻÓŁTY"]);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn guesses_in_other_languages_use_their_list() {
+        let dir = data_dir("guesses");
+        write(
+            &dir,
+            "languages/pl.ron",
+            "(words: [\"żabka\", \"żółty\"], validation: PackOnly)",
+        );
+        let words = Words {
+            words: vec![Word {
+                word: String::from("CRANE"),
+            }],
+            packs: HashMap::new(),
+            languages: load_word_lists(&dir, "languages"),
+        };
+
+        assert!(words.is_allowed_guess("ŻÓŁTY", None, Some("pl")));
+        assert!(!words.is_allowed_guess("CRANE", None, Some("pl")));
+        assert!(words.is_allowed_guess("CRANE", None, None));
+        assert!(!words.is_allowed_guess("ŻABKA", None, None));
+        assert_eq!(words.answers(None, Some("pl")), ["ŻABKA", "ŻÓŁTY"]);
+        assert_eq!(words.language_codes(), ["pl"]);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn main_list_is_normalized() {
+        let dir = data_dir("main-list");
+        write(
+            &dir,
+            "words.json",
+            r#"[{"word": "crane"}, {"word": "ab"}, {"word": "cranes"}, {"word": "Slate"}]"#,
+        );
+        let url = Path::new(&dir).join("words.json");
+        let words = Words::new(url.to_str().unwrap(), &dir).await;
+        assert_eq!(words.answers(None, None), ["CRANE", "SLATE"]);
+        assert!(!words.is_fallback());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn unreadable_main_list_falls_back() {
+        let dir = data_dir("no-list");
+        write(&dir, "words.json", r#"[{"word": "ab"}]"#);
+        let url = Path::new(&dir).join("words.json");
+        assert!(Words::new(url.to_str().unwrap(), &dir).await.is_fallback());
+        let url = Path::new(&dir).join("missing.json");
+        assert!(Words::new(url.to_str().unwrap(), &dir).await.is_fallback());
+        std::fs::remove_dir_all(dir).unwrap();
     }
 }